@@ -1,26 +1,157 @@
 #include <stdint.h>
 #include <string.h>
 
+#define BUDDY_MAX_ORDERS 48
+
+enum RegionClearPolicy {
+    REGION_CLEAR_KEEP_DATA,
+    REGION_CLEAR_ZERO,
+    REGION_CLEAR_POISON,
+    REGION_CLEAR_RELEASE,
+};
+
 struct RegionMemoryBuffer {
     uint64_t size;
     uint8_t* base;
     uintptr_t offset;
+    uint64_t committed;
+    uint64_t initial_commit;
+    bool owns_memory;
+    bool decommit_on_free;
+    uint8_t poison_pattern;
+    RegionClearPolicy clear_policy;
 };
 
 struct StackMemoryBuffer {
     uint64_t size;
     uint8_t* base;
     uintptr_t offset;
+    uintptr_t top;
+};
+
+struct PoolMemoryBuffer {
+    uint64_t size;
+    uint8_t* base;
+    uint64_t block_size;
+    uint64_t blocks_count;
+    uint64_t used_blocks;
+    uint64_t initialized_blocks;
+    uint8_t* free_list;
+};
+
+struct BuddyMemoryBuffer {
+    RegionMemoryBuffer region;
+    uint64_t size;
+    uint8_t* base;
+    uint64_t min_block_size;
+    uint32_t max_order;
+    uint64_t used_size;
+    uint8_t* block_orders;
+    uint8_t* free_lists[BUDDY_MAX_ORDERS];
+};
+
+struct BuddyMemoryStats {
+    uint64_t total_size;
+    uint64_t used_size;
+    uint64_t free_size;
+    uint64_t largest_free_block;
+    uint64_t free_blocks_count;
+};
+
+struct TlsfMemoryBuffer {
+    RegionMemoryBuffer region;
+    uint64_t size;
+    uint8_t* base;
+    uint64_t used_size;
+    uint8_t* control;
 };
 
-extern "C" uint8_t* virtual_alloc(uint32_t size);
+extern "C" uint8_t* virtual_alloc(uint64_t size);
+
+extern "C" void virtual_free(uint8_t* base, uint64_t size);
+
+extern "C" uint8_t* virtual_reserve(uint64_t size);
+
+extern "C" bool virtual_commit(uint8_t* base, uint64_t size);
+
+extern "C" void virtual_decommit(uint8_t* base, uint64_t size);
+
+extern "C" void virtual_release(uint8_t* base, uint64_t size);
+
+extern "C" uint64_t virtual_page_size();
 
 extern "C" RegionMemoryBuffer create_region_memory_buffer(uint64_t size);
 
+extern "C" RegionMemoryBuffer reserve_region_memory_buffer(uint64_t size, uint64_t initial_commit);
+
+extern "C" void region_memory_buffer_destroy(RegionMemoryBuffer* buffer);
+
 extern "C" RegionMemoryBuffer region_memory_buffer_emplace_region(RegionMemoryBuffer* where, uint64_t size);
 
 extern "C" uint8_t* region_memory_buffer_alloc(RegionMemoryBuffer* buffer, uint64_t size);
 
+extern "C" uint8_t* region_memory_buffer_alloc_aligned(RegionMemoryBuffer* buffer, uint64_t size, uint64_t align);
+
+extern "C" uint8_t* region_memory_buffer_realloc(RegionMemoryBuffer* buffer, uint8_t* ptr, uint64_t old_size, uint64_t new_size, uint64_t align);
+
 extern "C" uint8_t* region_memory_buffer_emplace(RegionMemoryBuffer* buffer, uint64_t size, uint8_t const* data);
 
+extern "C" uint8_t* region_memory_buffer_emplace_aligned(RegionMemoryBuffer* buffer, uint64_t size, uint64_t align, uint8_t const* data);
+
+extern "C" uintptr_t region_memory_buffer_get_marker(RegionMemoryBuffer const* buffer);
+
+extern "C" void region_memory_buffer_reset_to_marker(RegionMemoryBuffer* buffer, uintptr_t marker);
+
 extern "C" void region_memory_buffer_free(RegionMemoryBuffer* buffer);
+
+extern "C" StackMemoryBuffer create_stack_memory_buffer(uint64_t size);
+
+extern "C" void stack_memory_buffer_destroy(StackMemoryBuffer* buffer);
+
+extern "C" uint8_t* stack_memory_buffer_push(StackMemoryBuffer* buffer, uint64_t size, uint64_t align);
+
+extern "C" void stack_memory_buffer_pop(StackMemoryBuffer* buffer);
+
+extern "C" bool stack_memory_buffer_pop_to(StackMemoryBuffer* buffer, uint8_t* ptr);
+
+extern "C" void stack_memory_buffer_free(StackMemoryBuffer* buffer);
+
+extern "C" PoolMemoryBuffer create_pool_memory_buffer(uint64_t block_size, uint64_t align, uint64_t blocks_count);
+
+extern "C" void pool_memory_buffer_destroy(PoolMemoryBuffer* buffer);
+
+extern "C" uint8_t* pool_memory_buffer_alloc(PoolMemoryBuffer* buffer);
+
+extern "C" void pool_memory_buffer_free_block(PoolMemoryBuffer* buffer, uint8_t* ptr);
+
+extern "C" void pool_memory_buffer_free(PoolMemoryBuffer* buffer);
+
+extern "C" BuddyMemoryBuffer create_buddy_memory_buffer(uint64_t size, uint64_t min_block_size);
+
+extern "C" void buddy_memory_buffer_destroy(BuddyMemoryBuffer* buffer);
+
+extern "C" uint8_t* buddy_memory_buffer_alloc(BuddyMemoryBuffer* buffer, uint64_t size);
+
+extern "C" uint64_t buddy_memory_buffer_get_block_size(BuddyMemoryBuffer const* buffer, uint8_t const* ptr);
+
+extern "C" void buddy_memory_buffer_free_block(BuddyMemoryBuffer* buffer, uint8_t* ptr);
+
+extern "C" void buddy_memory_buffer_free(BuddyMemoryBuffer* buffer);
+
+extern "C" BuddyMemoryStats buddy_memory_buffer_get_stats(BuddyMemoryBuffer const* buffer);
+
+extern "C" TlsfMemoryBuffer create_tlsf_memory_buffer(uint64_t size);
+
+extern "C" void tlsf_memory_buffer_destroy(TlsfMemoryBuffer* buffer);
+
+extern "C" uint8_t* tlsf_memory_buffer_alloc(TlsfMemoryBuffer* buffer, uint64_t size);
+
+extern "C" uint8_t* tlsf_memory_buffer_realloc(TlsfMemoryBuffer* buffer, uint8_t* ptr, uint64_t new_size);
+
+extern "C" uint64_t tlsf_memory_buffer_get_block_size(TlsfMemoryBuffer const* buffer, uint8_t const* ptr);
+
+extern "C" uint64_t tlsf_memory_buffer_get_max_alloc_size(TlsfMemoryBuffer const* buffer);
+
+extern "C" void tlsf_memory_buffer_free_block(TlsfMemoryBuffer* buffer, uint8_t* ptr);
+
+extern "C" void tlsf_memory_buffer_free(TlsfMemoryBuffer* buffer);
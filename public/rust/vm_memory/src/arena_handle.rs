@@ -0,0 +1,54 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A checked reference to a value allocated by [`RegionAllocator::alloc_handle`].
+///
+/// The handle keeps the offset of the value and the generation of the region
+/// at the moment of allocation. Clearing or resetting the region changes the generation,
+/// so [`RegionAllocator::get`] detects handles that outlived their allocation.
+/// Generations are unique across allocators, so handles of another allocator are rejected too.
+///
+/// [`RegionAllocator::alloc_handle`]: crate::RegionAllocator::alloc_handle
+/// [`RegionAllocator::get`]: crate::RegionAllocator::get
+pub struct ArenaHandle<T> {
+    pub(crate) offset: usize,
+    pub(crate) generation: u64,
+    pub(crate) value: PhantomData<*const T>,
+}
+
+impl<T> ArenaHandle<T> {
+    /// Returns the offset of the value from the base of the region.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the generation of the region the value was allocated in.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl<T> Clone for ArenaHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaHandle<T> {}
+
+impl<T> PartialEq for ArenaHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for ArenaHandle<T> {}
+
+impl<T> fmt::Debug for ArenaHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaHandle")
+            .field("offset", &self.offset)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
@@ -0,0 +1,209 @@
+use crate::c_api::*;
+use crate::{AllocError, BufferAccessor};
+use std::cell::Cell;
+use std::io;
+
+/// Usage statistics of a [`BuddyAllocator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuddyStats {
+    /// The number of bytes managed by the allocator.
+    pub total_size: usize,
+    /// The number of bytes in allocated blocks, including the rounding up of the sizes.
+    pub used_size: usize,
+    /// The number of bytes in free blocks.
+    pub free_size: usize,
+    /// The size of the largest free block, the largest allocation that can succeed.
+    pub largest_free_block: usize,
+    /// The number of free blocks.
+    pub free_blocks_count: usize,
+}
+
+impl BuddyStats {
+    /// Returns the share of the free memory that can't be allocated in a single block,
+    /// from `0.0` when all the free memory is one block to close to `1.0`.
+    pub fn fragmentation(&self) -> f64 {
+        if self.free_size == 0 {
+            0.0
+        } else {
+            1.0 - self.largest_free_block as f64 / self.free_size as f64
+        }
+    }
+}
+
+/// Buddy allocator for power of two blocks.
+///
+/// The memory is split into blocks with sizes of power of two multiples of the minimal
+/// block size. An allocation gets the smallest block that fits it, larger free blocks
+/// are split in halves on demand. When a block is freed and its buddy, the other half of
+/// the same larger block, is free too, the halves are merged back.
+/// Free blocks are kept in one free list per order.
+///
+/// Blocks are aligned to their size up to the page size.
+/// The memory is released back to the system when the allocator is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let allocator = BuddyAllocator::new(4096, 64).unwrap();
+/// let first = allocator.alloc(1000).unwrap();
+/// let second = allocator.alloc(100).unwrap();
+///
+/// assert_eq!(2048, allocator.stats().largest_free_block);
+///
+/// unsafe {
+///     assert_eq!(1024, allocator.block_size(first));
+///     assert_eq!(128, allocator.block_size(second));
+///
+///     allocator.free(first);
+///     allocator.free(second);
+/// }
+///
+/// assert_eq!(4096, allocator.stats().largest_free_block);
+/// assert_eq!(1, allocator.stats().free_blocks_count);
+/// ```
+pub struct BuddyAllocator {
+    buddy: Cell<BuddyMemoryBuffer>,
+}
+
+impl BufferAccessor for BuddyAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.buffer().base
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.buffer().size
+    }
+}
+
+impl Drop for BuddyAllocator {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        unsafe { buddy_memory_buffer_destroy(self.buddy.get_mut()) };
+    }
+}
+
+impl BuddyAllocator {
+    /// Create a new allocator that manages up to `size` bytes split into blocks
+    /// of at least `min_block_size` bytes.
+    ///
+    /// Only the largest power of two multiple of `min_block_size` that fits the `size`
+    /// is managed, but at least one block.
+    ///
+    /// # Errors
+    ///
+    /// If `min_block_size` is not a power of two, the `size` can't be mapped on this platform
+    /// or the system fails to map the memory, then this call will return an error.
+    pub fn new(size: usize, min_block_size: usize) -> Result<Self, AllocError> {
+        if !min_block_size.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        if size > isize::MAX as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let buddy = unsafe { create_buddy_memory_buffer(size as u64, min_block_size as u64) };
+
+        if buddy.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else {
+            Ok(Self {
+                buddy: Cell::new(buddy),
+            })
+        }
+    }
+
+    /// Allocate the smallest block that fits a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If there is no free block large enough, then this call will return an error.
+    pub fn alloc(&self, size: usize) -> Result<*mut u8, AllocError> {
+        let data = unsafe { buddy_memory_buffer_alloc(self.buddy.as_ptr(), size as u64) };
+
+        if data.is_null() {
+            Err(AllocError::OutOfMemory {
+                requested: size,
+                available: self.stats().largest_free_block,
+            })
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Return the block at `ptr` to the allocator, merging it with its free buddies.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block allocated by this allocator that is not freed yet,
+    /// the block must not be used after this call.
+    pub unsafe fn free(&self, ptr: *mut u8) {
+        buddy_memory_buffer_free_block(self.buddy.as_ptr(), ptr);
+    }
+
+    /// Returns the size of the block allocated at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block allocated by this allocator that is not freed yet.
+    pub unsafe fn block_size(&self, ptr: *const u8) -> usize {
+        buddy_memory_buffer_get_block_size(self.buddy.as_ptr(), ptr) as usize
+    }
+
+    /// Returns the size of the smallest block.
+    pub fn min_block_size(&self) -> usize {
+        self.buffer().min_block_size as usize
+    }
+
+    /// Returns the usage statistics of the allocator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = BuddyAllocator::new(4096, 1024).unwrap();
+    /// let blocks = [
+    ///     allocator.alloc(1024).unwrap(),
+    ///     allocator.alloc(1024).unwrap(),
+    ///     allocator.alloc(1024).unwrap(),
+    ///     allocator.alloc(1024).unwrap(),
+    /// ];
+    ///
+    /// // Free blocks that are not buddies can't be merged.
+    /// unsafe {
+    ///     allocator.free(blocks[1]);
+    ///     allocator.free(blocks[2]);
+    /// }
+    ///
+    /// let stats = allocator.stats();
+    /// assert_eq!(2048, stats.free_size);
+    /// assert_eq!(1024, stats.largest_free_block);
+    /// assert_eq!(2, stats.free_blocks_count);
+    /// assert_eq!(0.5, stats.fragmentation());
+    /// ```
+    pub fn stats(&self) -> BuddyStats {
+        let stats = unsafe { buddy_memory_buffer_get_stats(self.buddy.as_ptr()) };
+
+        BuddyStats {
+            total_size: stats.total_size as usize,
+            used_size: stats.used_size as usize,
+            free_size: stats.free_size as usize,
+            largest_free_block: stats.largest_free_block as usize,
+            free_blocks_count: stats.free_blocks_count as usize,
+        }
+    }
+
+    /// Free all blocks.
+    pub fn clear(&mut self) {
+        unsafe { buddy_memory_buffer_free(self.buddy.get_mut()) };
+    }
+
+    // The buffer embeds the free lists, read the fields in place instead of copying it out.
+    fn buffer(&self) -> &BuddyMemoryBuffer {
+        unsafe { &*self.buddy.as_ptr() }
+    }
+}
@@ -145,6 +145,7 @@ pub const _STRING_H: u32 = 1;
 pub const _BITS_TYPES_LOCALE_T_H: u32 = 1;
 pub const _BITS_TYPES___LOCALE_T_H: u32 = 1;
 pub const _STRINGS_H: u32 = 1;
+pub const BUDDY_MAX_ORDERS: u32 = 48;
 pub type __u_char = ::std::os::raw::c_uchar;
 pub type __u_short = ::std::os::raw::c_ushort;
 pub type __u_int = ::std::os::raw::c_uint;
@@ -197,7 +198,7 @@ fn bindgen_test_layout___fsid_t() {
         concat!("Alignment of ", stringify!(__fsid_t))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__fsid_t>())).__val as *const _ as usize },
+        ::std::mem::offset_of!(__fsid_t, __val),
         0usize,
         concat!(
             "Offset of field: ",
@@ -384,7 +385,7 @@ fn bindgen_test_layout___locale_struct() {
         concat!("Alignment of ", stringify!(__locale_struct))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__locale_struct>())).__locales as *const _ as usize },
+        ::std::mem::offset_of!(__locale_struct, __locales),
         0usize,
         concat!(
             "Offset of field: ",
@@ -394,7 +395,7 @@ fn bindgen_test_layout___locale_struct() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__locale_struct>())).__ctype_b as *const _ as usize },
+        ::std::mem::offset_of!(__locale_struct, __ctype_b),
         104usize,
         concat!(
             "Offset of field: ",
@@ -404,7 +405,7 @@ fn bindgen_test_layout___locale_struct() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__locale_struct>())).__ctype_tolower as *const _ as usize },
+        ::std::mem::offset_of!(__locale_struct, __ctype_tolower),
         112usize,
         concat!(
             "Offset of field: ",
@@ -414,7 +415,7 @@ fn bindgen_test_layout___locale_struct() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__locale_struct>())).__ctype_toupper as *const _ as usize },
+        ::std::mem::offset_of!(__locale_struct, __ctype_toupper),
         120usize,
         concat!(
             "Offset of field: ",
@@ -424,7 +425,7 @@ fn bindgen_test_layout___locale_struct() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<__locale_struct>())).__names as *const _ as usize },
+        ::std::mem::offset_of!(__locale_struct, __names),
         128usize,
         concat!(
             "Offset of field: ",
@@ -703,18 +704,29 @@ extern "C" {
 extern "C" {
     pub fn basename(__filename: *mut ::std::os::raw::c_char) -> *mut ::std::os::raw::c_char;
 }
+pub const RegionClearPolicy_REGION_CLEAR_KEEP_DATA: RegionClearPolicy = 0;
+pub const RegionClearPolicy_REGION_CLEAR_ZERO: RegionClearPolicy = 1;
+pub const RegionClearPolicy_REGION_CLEAR_POISON: RegionClearPolicy = 2;
+pub const RegionClearPolicy_REGION_CLEAR_RELEASE: RegionClearPolicy = 3;
+pub type RegionClearPolicy = ::std::os::raw::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct RegionMemoryBuffer {
     pub size: u64,
     pub base: *mut u8,
     pub offset: usize,
+    pub committed: u64,
+    pub initial_commit: u64,
+    pub owns_memory: bool,
+    pub decommit_on_free: bool,
+    pub poison_pattern: u8,
+    pub clear_policy: RegionClearPolicy,
 }
 #[test]
 fn bindgen_test_layout_RegionMemoryBuffer() {
     assert_eq!(
         ::std::mem::size_of::<RegionMemoryBuffer>(),
-        24usize,
+        48usize,
         concat!("Size of: ", stringify!(RegionMemoryBuffer))
     );
     assert_eq!(
@@ -723,7 +735,7 @@ fn bindgen_test_layout_RegionMemoryBuffer() {
         concat!("Alignment of ", stringify!(RegionMemoryBuffer))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RegionMemoryBuffer>())).size as *const _ as usize },
+        ::std::mem::offset_of!(RegionMemoryBuffer, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -733,7 +745,7 @@ fn bindgen_test_layout_RegionMemoryBuffer() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RegionMemoryBuffer>())).base as *const _ as usize },
+        ::std::mem::offset_of!(RegionMemoryBuffer, base),
         8usize,
         concat!(
             "Offset of field: ",
@@ -743,7 +755,7 @@ fn bindgen_test_layout_RegionMemoryBuffer() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<RegionMemoryBuffer>())).offset as *const _ as usize },
+        ::std::mem::offset_of!(RegionMemoryBuffer, offset),
         16usize,
         concat!(
             "Offset of field: ",
@@ -752,6 +764,66 @@ fn bindgen_test_layout_RegionMemoryBuffer() {
             stringify!(offset)
         )
     );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, committed),
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(committed)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, initial_commit),
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(initial_commit)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, owns_memory),
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(owns_memory)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, decommit_on_free),
+        41usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(decommit_on_free)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, poison_pattern),
+        42usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(poison_pattern)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(RegionMemoryBuffer, clear_policy),
+        44usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(RegionMemoryBuffer),
+            "::",
+            stringify!(clear_policy)
+        )
+    );
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -759,12 +831,13 @@ pub struct StackMemoryBuffer {
     pub size: u64,
     pub base: *mut u8,
     pub offset: usize,
+    pub top: usize,
 }
 #[test]
 fn bindgen_test_layout_StackMemoryBuffer() {
     assert_eq!(
         ::std::mem::size_of::<StackMemoryBuffer>(),
-        24usize,
+        32usize,
         concat!("Size of: ", stringify!(StackMemoryBuffer))
     );
     assert_eq!(
@@ -773,7 +846,7 @@ fn bindgen_test_layout_StackMemoryBuffer() {
         concat!("Alignment of ", stringify!(StackMemoryBuffer))
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StackMemoryBuffer>())).size as *const _ as usize },
+        ::std::mem::offset_of!(StackMemoryBuffer, size),
         0usize,
         concat!(
             "Offset of field: ",
@@ -783,7 +856,7 @@ fn bindgen_test_layout_StackMemoryBuffer() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StackMemoryBuffer>())).base as *const _ as usize },
+        ::std::mem::offset_of!(StackMemoryBuffer, base),
         8usize,
         concat!(
             "Offset of field: ",
@@ -793,7 +866,7 @@ fn bindgen_test_layout_StackMemoryBuffer() {
         )
     );
     assert_eq!(
-        unsafe { &(*(::std::ptr::null::<StackMemoryBuffer>())).offset as *const _ as usize },
+        ::std::mem::offset_of!(StackMemoryBuffer, offset),
         16usize,
         concat!(
             "Offset of field: ",
@@ -802,13 +875,390 @@ fn bindgen_test_layout_StackMemoryBuffer() {
             stringify!(offset)
         )
     );
+    assert_eq!(
+        ::std::mem::offset_of!(StackMemoryBuffer, top),
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(StackMemoryBuffer),
+            "::",
+            stringify!(top)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PoolMemoryBuffer {
+    pub size: u64,
+    pub base: *mut u8,
+    pub block_size: u64,
+    pub blocks_count: u64,
+    pub used_blocks: u64,
+    pub initialized_blocks: u64,
+    pub free_list: *mut u8,
+}
+#[test]
+fn bindgen_test_layout_PoolMemoryBuffer() {
+    assert_eq!(
+        ::std::mem::size_of::<PoolMemoryBuffer>(),
+        56usize,
+        concat!("Size of: ", stringify!(PoolMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<PoolMemoryBuffer>(),
+        8usize,
+        concat!("Alignment of ", stringify!(PoolMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, size),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, base),
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(base)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, block_size),
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(block_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, blocks_count),
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(blocks_count)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, used_blocks),
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(used_blocks)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, initialized_blocks),
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(initialized_blocks)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(PoolMemoryBuffer, free_list),
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(PoolMemoryBuffer),
+            "::",
+            stringify!(free_list)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct BuddyMemoryBuffer {
+    pub region: RegionMemoryBuffer,
+    pub size: u64,
+    pub base: *mut u8,
+    pub min_block_size: u64,
+    pub max_order: u32,
+    pub used_size: u64,
+    pub block_orders: *mut u8,
+    pub free_lists: [*mut u8; 48usize],
+}
+#[test]
+fn bindgen_test_layout_BuddyMemoryBuffer() {
+    assert_eq!(
+        ::std::mem::size_of::<BuddyMemoryBuffer>(),
+        480usize,
+        concat!("Size of: ", stringify!(BuddyMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<BuddyMemoryBuffer>(),
+        8usize,
+        concat!("Alignment of ", stringify!(BuddyMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, region),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(region)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, size),
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, base),
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(base)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, min_block_size),
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(min_block_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, max_order),
+        72usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(max_order)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, used_size),
+        80usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(used_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, block_orders),
+        88usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(block_orders)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryBuffer, free_lists),
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryBuffer),
+            "::",
+            stringify!(free_lists)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct BuddyMemoryStats {
+    pub total_size: u64,
+    pub used_size: u64,
+    pub free_size: u64,
+    pub largest_free_block: u64,
+    pub free_blocks_count: u64,
+}
+#[test]
+fn bindgen_test_layout_BuddyMemoryStats() {
+    assert_eq!(
+        ::std::mem::size_of::<BuddyMemoryStats>(),
+        40usize,
+        concat!("Size of: ", stringify!(BuddyMemoryStats))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<BuddyMemoryStats>(),
+        8usize,
+        concat!("Alignment of ", stringify!(BuddyMemoryStats))
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryStats, total_size),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryStats),
+            "::",
+            stringify!(total_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryStats, used_size),
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryStats),
+            "::",
+            stringify!(used_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryStats, free_size),
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryStats),
+            "::",
+            stringify!(free_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryStats, largest_free_block),
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryStats),
+            "::",
+            stringify!(largest_free_block)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(BuddyMemoryStats, free_blocks_count),
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(BuddyMemoryStats),
+            "::",
+            stringify!(free_blocks_count)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TlsfMemoryBuffer {
+    pub region: RegionMemoryBuffer,
+    pub size: u64,
+    pub base: *mut u8,
+    pub used_size: u64,
+    pub control: *mut u8,
+}
+#[test]
+fn bindgen_test_layout_TlsfMemoryBuffer() {
+    assert_eq!(
+        ::std::mem::size_of::<TlsfMemoryBuffer>(),
+        80usize,
+        concat!("Size of: ", stringify!(TlsfMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<TlsfMemoryBuffer>(),
+        8usize,
+        concat!("Alignment of ", stringify!(TlsfMemoryBuffer))
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(TlsfMemoryBuffer, region),
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TlsfMemoryBuffer),
+            "::",
+            stringify!(region)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(TlsfMemoryBuffer, size),
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TlsfMemoryBuffer),
+            "::",
+            stringify!(size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(TlsfMemoryBuffer, base),
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TlsfMemoryBuffer),
+            "::",
+            stringify!(base)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(TlsfMemoryBuffer, used_size),
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TlsfMemoryBuffer),
+            "::",
+            stringify!(used_size)
+        )
+    );
+    assert_eq!(
+        ::std::mem::offset_of!(TlsfMemoryBuffer, control),
+        72usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(TlsfMemoryBuffer),
+            "::",
+            stringify!(control)
+        )
+    );
+}
+extern "C" {
+    pub fn virtual_alloc(size: u64) -> *mut u8;
 }
 extern "C" {
-    pub fn virtual_alloc(size: u32) -> *mut u8;
+    pub fn virtual_free(base: *mut u8, size: u64);
+}
+extern "C" {
+    pub fn virtual_reserve(size: u64) -> *mut u8;
+}
+extern "C" {
+    pub fn virtual_commit(base: *mut u8, size: u64) -> bool;
+}
+extern "C" {
+    pub fn virtual_decommit(base: *mut u8, size: u64);
+}
+extern "C" {
+    pub fn virtual_release(base: *mut u8, size: u64);
+}
+extern "C" {
+    pub fn virtual_page_size() -> u64;
 }
 extern "C" {
     pub fn create_region_memory_buffer(size: u64) -> RegionMemoryBuffer;
 }
+extern "C" {
+    pub fn reserve_region_memory_buffer(size: u64, initial_commit: u64) -> RegionMemoryBuffer;
+}
+extern "C" {
+    pub fn region_memory_buffer_destroy(buffer: *mut RegionMemoryBuffer);
+}
 extern "C" {
     pub fn region_memory_buffer_emplace_region(
         where_: *mut RegionMemoryBuffer,
@@ -818,6 +1268,22 @@ extern "C" {
 extern "C" {
     pub fn region_memory_buffer_alloc(buffer: *mut RegionMemoryBuffer, size: u64) -> *mut u8;
 }
+extern "C" {
+    pub fn region_memory_buffer_alloc_aligned(
+        buffer: *mut RegionMemoryBuffer,
+        size: u64,
+        align: u64,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn region_memory_buffer_realloc(
+        buffer: *mut RegionMemoryBuffer,
+        ptr: *mut u8,
+        old_size: u64,
+        new_size: u64,
+        align: u64,
+    ) -> *mut u8;
+}
 extern "C" {
     pub fn region_memory_buffer_emplace(
         buffer: *mut RegionMemoryBuffer,
@@ -825,9 +1291,119 @@ extern "C" {
         data: *const u8,
     ) -> *mut u8;
 }
+extern "C" {
+    pub fn region_memory_buffer_emplace_aligned(
+        buffer: *mut RegionMemoryBuffer,
+        size: u64,
+        align: u64,
+        data: *const u8,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn region_memory_buffer_get_marker(buffer: *const RegionMemoryBuffer) -> usize;
+}
+extern "C" {
+    pub fn region_memory_buffer_reset_to_marker(buffer: *mut RegionMemoryBuffer, marker: usize);
+}
 extern "C" {
     pub fn region_memory_buffer_free(buffer: *mut RegionMemoryBuffer);
 }
+extern "C" {
+    pub fn create_stack_memory_buffer(size: u64) -> StackMemoryBuffer;
+}
+extern "C" {
+    pub fn stack_memory_buffer_destroy(buffer: *mut StackMemoryBuffer);
+}
+extern "C" {
+    pub fn stack_memory_buffer_push(
+        buffer: *mut StackMemoryBuffer,
+        size: u64,
+        align: u64,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn stack_memory_buffer_pop(buffer: *mut StackMemoryBuffer);
+}
+extern "C" {
+    pub fn stack_memory_buffer_pop_to(buffer: *mut StackMemoryBuffer, ptr: *mut u8) -> bool;
+}
+extern "C" {
+    pub fn stack_memory_buffer_free(buffer: *mut StackMemoryBuffer);
+}
+extern "C" {
+    pub fn create_pool_memory_buffer(
+        block_size: u64,
+        align: u64,
+        blocks_count: u64,
+    ) -> PoolMemoryBuffer;
+}
+extern "C" {
+    pub fn pool_memory_buffer_destroy(buffer: *mut PoolMemoryBuffer);
+}
+extern "C" {
+    pub fn pool_memory_buffer_alloc(buffer: *mut PoolMemoryBuffer) -> *mut u8;
+}
+extern "C" {
+    pub fn pool_memory_buffer_free_block(buffer: *mut PoolMemoryBuffer, ptr: *mut u8);
+}
+extern "C" {
+    pub fn pool_memory_buffer_free(buffer: *mut PoolMemoryBuffer);
+}
+extern "C" {
+    pub fn create_buddy_memory_buffer(size: u64, min_block_size: u64) -> BuddyMemoryBuffer;
+}
+extern "C" {
+    pub fn buddy_memory_buffer_destroy(buffer: *mut BuddyMemoryBuffer);
+}
+extern "C" {
+    pub fn buddy_memory_buffer_alloc(buffer: *mut BuddyMemoryBuffer, size: u64) -> *mut u8;
+}
+extern "C" {
+    pub fn buddy_memory_buffer_get_block_size(
+        buffer: *const BuddyMemoryBuffer,
+        ptr: *const u8,
+    ) -> u64;
+}
+extern "C" {
+    pub fn buddy_memory_buffer_free_block(buffer: *mut BuddyMemoryBuffer, ptr: *mut u8);
+}
+extern "C" {
+    pub fn buddy_memory_buffer_free(buffer: *mut BuddyMemoryBuffer);
+}
+extern "C" {
+    pub fn buddy_memory_buffer_get_stats(buffer: *const BuddyMemoryBuffer) -> BuddyMemoryStats;
+}
+extern "C" {
+    pub fn create_tlsf_memory_buffer(size: u64) -> TlsfMemoryBuffer;
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_destroy(buffer: *mut TlsfMemoryBuffer);
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_alloc(buffer: *mut TlsfMemoryBuffer, size: u64) -> *mut u8;
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_realloc(
+        buffer: *mut TlsfMemoryBuffer,
+        ptr: *mut u8,
+        new_size: u64,
+    ) -> *mut u8;
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_get_block_size(
+        buffer: *const TlsfMemoryBuffer,
+        ptr: *const u8,
+    ) -> u64;
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_get_max_alloc_size(buffer: *const TlsfMemoryBuffer) -> u64;
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_free_block(buffer: *mut TlsfMemoryBuffer, ptr: *mut u8);
+}
+extern "C" {
+    pub fn tlsf_memory_buffer_free(buffer: *mut TlsfMemoryBuffer);
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct __locale_data {
@@ -0,0 +1,256 @@
+use crate::{AllocError, BufferAccessor, RegionAllocator};
+use std::alloc::Layout;
+use std::mem;
+
+/// Growth factor used by [`ChainedRegionAllocator::new`].
+pub const DEFAULT_GROWTH_FACTOR: usize = 2;
+
+/// Region based allocator that grows when the current region is exhausted.
+///
+/// Allocator keeps a list of blocks, each block is a [`RegionAllocator`].
+/// Whenever the current block can't fit an allocation, the allocator moves
+/// to the next block, requesting a new one from the system if there are no more blocks.
+/// Every new block is `growth_factor` times larger than the previous one.
+pub struct ChainedRegionAllocator {
+    blocks: Vec<RegionAllocator>,
+    current: usize,
+    next_block_size: usize,
+    growth_factor: usize,
+}
+
+impl ChainedRegionAllocator {
+    /// Create a new allocator with the first block of a specific size,
+    /// every next block is twice as large as the previous one.
+    ///
+    /// # Errors
+    ///
+    /// If the system fails to map the first block, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = ChainedRegionAllocator::new(1024).unwrap();
+    /// assert_eq!(1, allocator.get_blocks_count());
+    /// assert_eq!(1024, allocator.get_total_size());
+    /// ```
+    pub fn new(block_size: usize) -> Result<Self, AllocError> {
+        Self::with_growth_factor(block_size, DEFAULT_GROWTH_FACTOR)
+    }
+
+    /// Create a new allocator with the first block of a specific size,
+    /// every next block is `growth_factor` times larger than the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `growth_factor` is zero.
+    ///
+    /// # Errors
+    ///
+    /// If the system fails to map the first block, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::with_growth_factor(1024, 1).unwrap();
+    /// allocator.alloc(1024).unwrap();
+    /// allocator.alloc(1024).unwrap();
+    ///
+    /// assert_eq!(2, allocator.get_blocks_count());
+    /// assert_eq!(2048, allocator.get_total_size());
+    /// ```
+    pub fn with_growth_factor(block_size: usize, growth_factor: usize) -> Result<Self, AllocError> {
+        assert!(growth_factor > 0, "growth factor should be at least 1");
+
+        Ok(Self {
+            blocks: vec![RegionAllocator::new(block_size)?],
+            current: 0,
+            next_block_size: block_size.saturating_mul(growth_factor),
+            growth_factor,
+        })
+    }
+
+    /// Allocate a new chunk of memory with a specific size.
+    /// returns the base address of the allocated chunk of memory.
+    ///
+    /// # Errors
+    ///
+    /// If the system fails to map a new block, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(1000).unwrap();
+    /// allocator.alloc(1000).unwrap();
+    /// allocator.alloc(5000).unwrap();
+    ///
+    /// assert_eq!(3, allocator.get_blocks_count());
+    /// assert_eq!(1024 + 2048 + 5000, allocator.get_total_size());
+    /// assert_eq!(7000, allocator.get_used_size());
+    /// ```
+    pub fn alloc(&mut self, size: usize) -> Result<*mut u8, AllocError> {
+        self.alloc_aligned(size, 1)
+    }
+
+    /// Allocate a new chunk of memory with a specific size, the returned address
+    /// is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If `align` is not a power of two or the system fails to map a new block,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(1023).unwrap();
+    /// let base = allocator.alloc_aligned(64, 64).unwrap();
+    ///
+    /// assert_eq!(0, base as usize % 64);
+    /// assert_eq!(2, allocator.get_blocks_count());
+    /// ```
+    pub fn alloc_aligned(&mut self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        while self.current < self.blocks.len() {
+            match self.blocks[self.current].alloc_aligned(size, align) {
+                Err(AllocError::OutOfMemory { .. }) => self.current += 1,
+                result => return result,
+            }
+        }
+
+        let block_size = size
+            .checked_add(align - 1)
+            .ok_or(AllocError::RegionTooLarge)?
+            .max(self.next_block_size);
+
+        let block = RegionAllocator::new(block_size)?;
+        let data = block.alloc_aligned(size, align)?;
+
+        self.blocks.push(block);
+        self.current = self.blocks.len() - 1;
+        self.next_block_size = self.next_block_size.saturating_mul(self.growth_factor);
+
+        Ok(data)
+    }
+
+    /// Allocate a new chunk of memory that fits the `layout`.
+    ///
+    /// # Errors
+    ///
+    /// If the system fails to map a new block, then this call will return an error.
+    pub fn alloc_layout(&mut self, layout: Layout) -> Result<*mut u8, AllocError> {
+        self.alloc_aligned(layout.size(), layout.align())
+    }
+
+    /// Allocate a new region of memory with size and alignment of `T` and emplace the `value`
+    /// to the allocated memory.
+    ///
+    /// Returns a reference to the struct located in the memory of the allocator.
+    ///
+    /// # Errors
+    ///
+    /// If the system fails to map a new block, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::new(2).unwrap();
+    /// let data = allocator.emplace_struct(&12u64).unwrap();
+    /// *data += 1;
+    ///
+    /// assert_eq!(13, *data);
+    /// ```
+    pub fn emplace_struct<T: Copy>(&mut self, value: &T) -> Result<&mut T, AllocError> {
+        let data = self.alloc_aligned(mem::size_of::<T>(), mem::align_of::<T>())? as *mut T;
+
+        unsafe {
+            data.write(*value);
+            Ok(&mut *data)
+        }
+    }
+
+    /// Free all memory, the blocks are kept and reused by the next allocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(1024).unwrap();
+    /// allocator.alloc(2048).unwrap();
+    /// allocator.clear();
+    ///
+    /// assert_eq!(0, allocator.get_used_size());
+    ///
+    /// allocator.alloc(1024).unwrap();
+    /// allocator.alloc(2048).unwrap();
+    /// assert_eq!(2, allocator.get_blocks_count());
+    /// ```
+    pub fn clear(&mut self) {
+        for block in &mut self.blocks {
+            block.clear();
+        }
+
+        self.current = 0;
+    }
+
+    /// Free all memory and release every block except the first one back to the system.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = ChainedRegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(4096).unwrap();
+    /// allocator.release();
+    ///
+    /// assert_eq!(1, allocator.get_blocks_count());
+    /// assert_eq!(1024, allocator.get_total_size());
+    /// ```
+    pub fn release(&mut self) {
+        self.blocks.truncate(1);
+        self.clear();
+        self.next_block_size = self.blocks[0]
+            .get_buffer_size()
+            .saturating_mul(self.growth_factor as u64) as usize;
+    }
+
+    /// Returns the blocks owned by the allocator.
+    pub fn blocks(&self) -> &[RegionAllocator] {
+        &self.blocks
+    }
+
+    /// Returns the number of blocks owned by the allocator.
+    pub fn get_blocks_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns the capacity across all blocks.
+    pub fn get_total_size(&self) -> u64 {
+        self.blocks
+            .iter()
+            .map(|block| block.get_buffer_size())
+            .sum()
+    }
+
+    /// Returns the number of bytes used across all blocks, including alignment padding.
+    pub fn get_used_size(&self) -> u64 {
+        self.blocks.iter().map(|block| block.offset() as u64).sum()
+    }
+}
@@ -0,0 +1,39 @@
+use crate::c_api::*;
+
+/// Describes what happens with the used memory of a region when the region is cleared.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ClearPolicy {
+    /// Leave the stale data in place, the cheapest option.
+    #[default]
+    KeepData,
+    /// Fill the used memory with zeroes.
+    Zero,
+    /// Fill the used memory with a pattern, helps to catch reads of freed memory.
+    Poison(u8),
+    /// Give the used pages back to the system, they are zero filled on next touch.
+    Release,
+}
+
+impl ClearPolicy {
+    pub(crate) fn apply(self, region: &mut RegionMemoryBuffer) {
+        let (policy, pattern) = match self {
+            ClearPolicy::KeepData => (RegionClearPolicy_REGION_CLEAR_KEEP_DATA, 0),
+            ClearPolicy::Zero => (RegionClearPolicy_REGION_CLEAR_ZERO, 0),
+            ClearPolicy::Poison(pattern) => (RegionClearPolicy_REGION_CLEAR_POISON, pattern),
+            ClearPolicy::Release => (RegionClearPolicy_REGION_CLEAR_RELEASE, 0),
+        };
+
+        region.clear_policy = policy;
+        region.poison_pattern = pattern;
+    }
+
+    #[allow(non_upper_case_globals)]
+    pub(crate) fn from_region(region: &RegionMemoryBuffer) -> Self {
+        match region.clear_policy {
+            RegionClearPolicy_REGION_CLEAR_ZERO => ClearPolicy::Zero,
+            RegionClearPolicy_REGION_CLEAR_POISON => ClearPolicy::Poison(region.poison_pattern),
+            RegionClearPolicy_REGION_CLEAR_RELEASE => ClearPolicy::Release,
+            _ => ClearPolicy::KeepData,
+        }
+    }
+}
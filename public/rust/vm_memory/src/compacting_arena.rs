@@ -0,0 +1,330 @@
+use crate::{AllocError, BufferAccessor, Handle, RegionAllocator, RegionMarker};
+use std::mem;
+use std::ptr;
+use std::slice;
+
+const NO_ENTRY: u32 = u32::MAX;
+
+/// An entry of the indirection table.
+#[derive(Clone, Copy)]
+struct Entry {
+    /// The offsets of the header and the object from the base of the region.
+    header: usize,
+    offset: usize,
+    size: usize,
+    generation: u32,
+    /// The next entry in the free list.
+    next_free: u32,
+    live: bool,
+}
+
+/// Stored right before the alignment padding of every object,
+/// so compaction walks the objects in the order of addresses.
+#[derive(Clone, Copy)]
+struct ObjectHeader {
+    /// The index of the entry of the object, `NO_ENTRY` if the object is freed.
+    index: u32,
+    align: u32,
+    size: usize,
+}
+
+/// Region based arena for objects that are reached only through handles,
+/// so the objects can be moved to reclaim the memory of freed objects.
+///
+/// The indirection table that maps handles to the offsets of the objects lives at the
+/// beginning of the region, the objects are bump allocated after it. Freeing an object
+/// only marks it as dead, [`compact`](Self::compact) slides the live objects down over
+/// the dead ones, rewrites the table and moves the offset of the region back.
+///
+/// References to the objects borrow the arena, so the borrow checker rejects any use
+/// of them after compaction.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut arena = CompactingArena::new(4096, 16).unwrap();
+/// let first = arena.alloc(100, 8).unwrap();
+/// let second = arena.alloc(100, 8).unwrap();
+/// arena.get_mut(second).unwrap().fill(42);
+///
+/// let used = arena.used_size();
+/// arena.free(first).unwrap();
+/// assert_eq!(used, arena.used_size());
+///
+/// let moved = arena.compact();
+/// assert_eq!(100, moved);
+/// assert!(arena.used_size() < used);
+/// assert_eq!(&[42; 100][..], arena.get(second).unwrap());
+/// assert_eq!(Err(AllocError::StaleHandle), arena.get(first));
+/// ```
+pub struct CompactingArena {
+    allocator: RegionAllocator,
+    /// The offset of the first object, right after the indirection table.
+    heap_start: usize,
+    capacity: usize,
+    /// The number of entries that were used at least once.
+    entries_len: usize,
+    free_entry: u32,
+    live_size: usize,
+}
+
+impl BufferAccessor for CompactingArena {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.allocator.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.allocator.get_buffer_size()
+    }
+}
+
+impl CompactingArena {
+    /// Create a new arena with a specific size, that holds up to `capacity` objects at once.
+    ///
+    /// The indirection table takes a part of the region.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform, the system fails to map the memory
+    /// or the indirection table doesn't fit the `size`, then this call will return an error.
+    pub fn new(size: usize, capacity: usize) -> Result<Self, AllocError> {
+        if capacity >= NO_ENTRY as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let table_size = capacity
+            .checked_mul(mem::size_of::<Entry>())
+            .ok_or(AllocError::RegionTooLarge)?;
+
+        let allocator = RegionAllocator::new(size)?;
+        allocator.alloc_aligned(table_size, mem::align_of::<Entry>())?;
+
+        Ok(Self {
+            heap_start: allocator.offset(),
+            allocator,
+            capacity,
+            entries_len: 0,
+            free_entry: NO_ENTRY,
+            live_size: 0,
+        })
+    }
+
+    /// Allocate a new object with a specific size, the object is aligned to `align` bytes.
+    ///
+    /// Returns a handle to the object. The contents of the object are unspecified, the region
+    /// is zero filled by the system, but the memory can still hold the data of objects that
+    /// were moved or freed before.
+    ///
+    /// # Errors
+    ///
+    /// If the region is full, the indirection table is full or `align` is not a power
+    /// of two, then this call will return an error.
+    pub fn alloc(&mut self, size: usize, align: usize) -> Result<Handle, AllocError> {
+        if !align.is_power_of_two() || align > u32::MAX as usize {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        if self.free_entry == NO_ENTRY && self.entries_len == self.capacity {
+            return Err(AllocError::OutOfMemory {
+                requested: size,
+                available: 0,
+            });
+        }
+
+        let start = self.allocator.offset();
+        let (header, object, end) =
+            self.layout(start, size, align)
+                .ok_or(AllocError::OutOfMemory {
+                    requested: size,
+                    available: self.get_buffer_size() as usize - start,
+                })?;
+
+        self.allocator.alloc(end - start)?;
+
+        let index = if self.free_entry != NO_ENTRY {
+            let index = self.free_entry;
+            self.free_entry = self.entry(index).next_free;
+            index
+        } else {
+            self.entries_len += 1;
+            let index = (self.entries_len - 1) as u32;
+            *self.entry_mut(index) = Entry {
+                header: 0,
+                offset: 0,
+                size: 0,
+                generation: 0,
+                next_free: NO_ENTRY,
+                live: false,
+            };
+            index
+        };
+
+        let entry = self.entry_mut(index);
+        entry.header = header;
+        entry.offset = object;
+        entry.size = size;
+        entry.live = true;
+        let generation = entry.generation;
+
+        unsafe {
+            self.header_ptr(header).write(ObjectHeader {
+                index,
+                align: align as u32,
+                size,
+            })
+        };
+
+        self.live_size += size;
+        Ok(Handle { index, generation })
+    }
+
+    /// Returns the memory of the object of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the object of the `handle` was freed, then this call will return an error.
+    pub fn get(&self, handle: Handle) -> Result<&[u8], AllocError> {
+        let entry = self.live_entry(handle)?;
+
+        Ok(unsafe { slice::from_raw_parts(self.get_buffer_ptr().add(entry.offset), entry.size) })
+    }
+
+    /// Returns the mutable memory of the object of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the object of the `handle` was freed, then this call will return an error.
+    pub fn get_mut(&mut self, handle: Handle) -> Result<&mut [u8], AllocError> {
+        let entry = self.live_entry(handle)?;
+
+        Ok(unsafe {
+            slice::from_raw_parts_mut(self.get_buffer_ptr().add(entry.offset), entry.size)
+        })
+    }
+
+    /// Free the object of the `handle`, the memory is reclaimed by the next compaction.
+    ///
+    /// # Errors
+    ///
+    /// If the object of the `handle` was already freed, then this call will return an error.
+    pub fn free(&mut self, handle: Handle) -> Result<(), AllocError> {
+        let Entry { header, size, .. } = self.live_entry(handle)?;
+        unsafe { (*self.header_ptr(header)).index = NO_ENTRY };
+
+        let free_entry = self.free_entry;
+        let entry = self.entry_mut(handle.index);
+        entry.generation = entry.generation.wrapping_add(1);
+        entry.next_free = free_entry;
+        entry.live = false;
+
+        self.free_entry = handle.index;
+        self.live_size -= size;
+        Ok(())
+    }
+
+    /// Slide the live objects down over the freed ones and give the reclaimed memory
+    /// back to the region, the handles stay valid.
+    ///
+    /// Returns the number of bytes of the objects that were moved.
+    pub fn compact(&mut self) -> usize {
+        let end = self.allocator.offset();
+        let mut read = self.heap_start;
+        let mut write = self.heap_start;
+        let mut moved = 0;
+
+        while read < end {
+            let header = unsafe { self.header_ptr(read).read() };
+            let align = header.align as usize;
+            let (_, object, next) = self.layout(read, header.size, align).unwrap();
+
+            if header.index != NO_ENTRY {
+                let (_, new_object, new_next) = self.layout(write, header.size, align).unwrap();
+
+                if write != read {
+                    unsafe {
+                        let base = self.get_buffer_ptr();
+                        ptr::copy(base.add(object), base.add(new_object), header.size);
+                        self.header_ptr(write).write(header);
+                    }
+
+                    let entry = self.entry_mut(header.index);
+                    entry.header = write;
+                    entry.offset = new_object;
+
+                    if new_object != object {
+                        moved += header.size;
+                    }
+                }
+
+                write = new_next;
+            }
+
+            read = next;
+        }
+
+        self.allocator.reset_to(RegionMarker(write));
+        moved
+    }
+
+    /// Returns the number of bytes of the region in use, including the indirection table,
+    /// the freed objects and the bookkeeping of the objects.
+    pub fn used_size(&self) -> usize {
+        self.allocator.offset()
+    }
+
+    /// Returns the number of bytes of the live objects.
+    pub fn live_size(&self) -> usize {
+        self.live_size
+    }
+
+    /// Returns the number of objects the arena holds at once.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the offsets of the header, the object and the end of an object
+    /// placed at the `start` offset.
+    fn layout(&self, start: usize, size: usize, align: usize) -> Option<(usize, usize, usize)> {
+        let base = self.get_buffer_ptr() as usize;
+        let header = align_up(start, mem::align_of::<ObjectHeader>());
+        let object = align_up(base + header + mem::size_of::<ObjectHeader>(), align) - base;
+        let end = object
+            .checked_add(size)
+            .map(|end| align_up(end, mem::align_of::<ObjectHeader>()))
+            .filter(|&end| end <= self.get_buffer_size() as usize)?;
+
+        Some((header, object, end))
+    }
+
+    fn header_ptr(&self, offset: usize) -> *mut ObjectHeader {
+        unsafe { self.get_buffer_ptr().add(offset) as *mut ObjectHeader }
+    }
+
+    fn live_entry(&self, handle: Handle) -> Result<Entry, AllocError> {
+        if handle.index as usize >= self.entries_len {
+            return Err(AllocError::StaleHandle);
+        }
+
+        let entry = *self.entry(handle.index);
+
+        if entry.generation != handle.generation || !entry.live {
+            Err(AllocError::StaleHandle)
+        } else {
+            Ok(entry)
+        }
+    }
+
+    fn entry(&self, index: u32) -> &Entry {
+        unsafe { &*(self.get_buffer_ptr() as *const Entry).add(index as usize) }
+    }
+
+    fn entry_mut(&mut self, index: u32) -> &mut Entry {
+        unsafe { &mut *(self.get_buffer_ptr() as *mut Entry).add(index as usize) }
+    }
+}
+
+fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
@@ -0,0 +1,176 @@
+use crate::{AllocError, BufferAccessor, RegionAllocator};
+use std::cell::Cell;
+use std::mem;
+
+/// Region based allocator that allocates from both ends of the region.
+///
+/// Bottom allocations grow up from the base of the region, top allocations grow down
+/// from the end of the region. Both ends are cleared independently, so long-lived data
+/// can be placed at the bottom and per-frame scratch data at the top of a single mapping.
+/// Allocation fails when the two ends meet.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut region = DoubleEndedRegion::new(1024).unwrap();
+/// let level = region.emplace_bottom([1u32; 64]).unwrap();
+/// let scratch = region.emplace_top([2u32; 128]).unwrap();
+///
+/// assert_eq!(1, level[0]);
+/// assert_eq!(2, scratch[0]);
+/// assert_eq!(256, region.bottom_offset());
+/// assert_eq!(512, region.top_size());
+/// assert!(region.alloc_bottom(512, 1).is_err());
+///
+/// region.clear_top();
+/// assert_eq!(256, region.bottom_offset());
+/// assert_eq!(0, region.top_size());
+/// region.alloc_bottom(512, 1).unwrap();
+/// ```
+pub struct DoubleEndedRegion {
+    allocator: RegionAllocator,
+    bottom: Cell<usize>,
+    top: Cell<usize>,
+}
+
+impl BufferAccessor for DoubleEndedRegion {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.allocator.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.allocator.get_buffer_size()
+    }
+}
+
+impl DoubleEndedRegion {
+    /// Create a new double ended region with a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        Ok(Self {
+            allocator: RegionAllocator::new(size)?,
+            bottom: Cell::new(0),
+            top: Cell::new(size),
+        })
+    }
+
+    /// Allocate a new chunk of memory at the bottom of the region, the returned address
+    /// is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the ends of the region meet or `align` is not a power of two,
+    /// then this call will return an error.
+    pub fn alloc_bottom(&self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let base = self.get_buffer_ptr() as usize;
+        let start = (base + self.bottom.get() + align - 1) & !(align - 1);
+        let end = start
+            .checked_add(size)
+            .filter(|&end| end <= base + self.top.get())
+            .ok_or_else(|| self.out_of_memory(size))?;
+
+        self.bottom.set(end - base);
+        Ok(start as *mut u8)
+    }
+
+    /// Allocate a new chunk of memory at the top of the region, the returned address
+    /// is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the ends of the region meet or `align` is not a power of two,
+    /// then this call will return an error.
+    pub fn alloc_top(&self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let base = self.get_buffer_ptr() as usize;
+        let start = (base + self.top.get())
+            .checked_sub(size)
+            .map(|start| start & !(align - 1))
+            .filter(|&start| start >= base + self.bottom.get())
+            .ok_or_else(|| self.out_of_memory(size))?;
+
+        self.top.set(start - base);
+        Ok(start as *mut u8)
+    }
+
+    /// Move the `value` to the bottom of the region.
+    ///
+    /// # Errors
+    ///
+    /// If the ends of the region meet, then this call will return an error.
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_bottom<T>(&self, value: T) -> Result<&mut T, AllocError> {
+        let data = self.alloc_bottom(mem::size_of::<T>(), mem::align_of::<T>())? as *mut T;
+
+        unsafe {
+            data.write(value);
+            Ok(&mut *data)
+        }
+    }
+
+    /// Move the `value` to the top of the region.
+    ///
+    /// # Errors
+    ///
+    /// If the ends of the region meet, then this call will return an error.
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_top<T>(&self, value: T) -> Result<&mut T, AllocError> {
+        let data = self.alloc_top(mem::size_of::<T>(), mem::align_of::<T>())? as *mut T;
+
+        unsafe {
+            data.write(value);
+            Ok(&mut *data)
+        }
+    }
+
+    /// Returns the number of bytes allocated at the bottom of the region.
+    pub fn bottom_offset(&self) -> usize {
+        self.bottom.get()
+    }
+
+    /// Returns the number of bytes allocated at the top of the region.
+    pub fn top_size(&self) -> usize {
+        self.get_buffer_size() as usize - self.top.get()
+    }
+
+    /// Returns the number of bytes left between the ends of the region.
+    pub fn available(&self) -> usize {
+        self.top.get() - self.bottom.get()
+    }
+
+    /// Free all memory allocated at the bottom of the region.
+    pub fn clear_bottom(&mut self) {
+        self.bottom.set(0);
+    }
+
+    /// Free all memory allocated at the top of the region.
+    pub fn clear_top(&mut self) {
+        self.top.set(self.get_buffer_size() as usize);
+    }
+
+    /// Free all memory.
+    pub fn clear(&mut self) {
+        self.clear_bottom();
+        self.clear_top();
+    }
+
+    fn out_of_memory(&self, requested: usize) -> AllocError {
+        AllocError::OutOfMemory {
+            requested,
+            available: self.available(),
+        }
+    }
+}
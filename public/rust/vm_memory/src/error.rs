@@ -0,0 +1,50 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error returned by the allocators of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocError {
+    /// There is not enough free memory left in the region to fit the allocation.
+    OutOfMemory {
+        /// The number of bytes requested by the allocation.
+        requested: usize,
+        /// The number of bytes left in the region.
+        available: usize,
+    },
+    /// The system refused to map the memory, holds the `errno` reported by the system.
+    MappingFailed(i32),
+    /// The requested alignment is not a power of two or is not supported by the allocator.
+    InvalidAlignment,
+    /// The requested region size can't be mapped on this platform.
+    RegionTooLarge,
+    /// The requested allocation size is not supported by the allocator.
+    InvalidSize,
+    /// The handle refers to memory that was freed since the handle was created.
+    StaleHandle,
+}
+
+impl fmt::Display for AllocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocError::OutOfMemory {
+                requested,
+                available,
+            } => write!(
+                f,
+                "Out of memory: requested {} bytes, {} bytes available",
+                requested, available
+            ),
+            AllocError::MappingFailed(errno) => {
+                write!(f, "Failed to map memory (errno {})", errno)
+            }
+            AllocError::InvalidAlignment => {
+                write!(f, "Alignment is not a power of two or is not supported")
+            }
+            AllocError::RegionTooLarge => write!(f, "Region is too large"),
+            AllocError::InvalidSize => write!(f, "Allocation size is not supported"),
+            AllocError::StaleHandle => write!(f, "Handle refers to freed memory"),
+        }
+    }
+}
+
+impl Error for AllocError {}
@@ -0,0 +1,115 @@
+use crate::{AllocError, RegionAllocator};
+
+/// Double buffered allocator for per-frame data.
+///
+/// Owns two regions and alternates between them on every [`swap`](Self::swap),
+/// so the data allocated during a frame stays readable during the next frame.
+/// The region that is reused for the new frame is cleared on swap.
+///
+/// References to the frame data borrow the allocator, use [`ArenaHandle`]s
+/// to reach the previous frame data after a swap.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut frames = FrameAllocator::new(1024).unwrap();
+/// let handle = frames.current().alloc_handle(42u32).unwrap();
+///
+/// frames.swap();
+/// assert_eq!(Ok(&42), frames.previous().get(&handle));
+/// assert_eq!(Err(AllocError::StaleHandle), frames.current().get(&handle));
+///
+/// frames.swap();
+/// assert_eq!(Err(AllocError::StaleHandle), frames.current().get(&handle));
+/// ```
+///
+/// [`ArenaHandle`]: crate::ArenaHandle
+pub struct FrameAllocator {
+    frames: [RegionAllocator; 2],
+    current: usize,
+    frame: u64,
+    last_frame_usage: usize,
+    high_water_mark: usize,
+}
+
+impl FrameAllocator {
+    /// Create a new allocator, each frame gets a region with a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `frame_size` can't be mapped on this platform or the system fails
+    /// to map the memory, then this call will return an error.
+    pub fn new(frame_size: usize) -> Result<Self, AllocError> {
+        Ok(Self {
+            frames: [
+                RegionAllocator::new(frame_size)?,
+                RegionAllocator::new(frame_size)?,
+            ],
+            current: 0,
+            frame: 0,
+            last_frame_usage: 0,
+            high_water_mark: 0,
+        })
+    }
+
+    /// Returns the allocator of the current frame.
+    pub fn current(&self) -> &RegionAllocator {
+        &self.frames[self.current]
+    }
+
+    /// Returns the allocator of the previous frame.
+    pub fn previous(&self) -> &RegionAllocator {
+        &self.frames[1 - self.current]
+    }
+
+    /// Finish the current frame and start a new one.
+    ///
+    /// The current frame becomes the previous one, the region of the frame
+    /// before it is cleared and used for the new frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut frames = FrameAllocator::new(1024).unwrap();
+    /// frames.current().alloc(512).unwrap();
+    /// frames.swap();
+    /// frames.current().alloc(128).unwrap();
+    /// frames.swap();
+    ///
+    /// assert_eq!(2, frames.frame());
+    /// assert_eq!(128, frames.last_frame_usage());
+    /// assert_eq!(512, frames.high_water_mark());
+    /// assert_eq!(0, frames.current().offset());
+    /// ```
+    pub fn swap(&mut self) {
+        self.last_frame_usage = self.current().offset();
+        self.high_water_mark = self.high_water_mark.max(self.last_frame_usage);
+        self.current = 1 - self.current;
+        self.frames[self.current].clear();
+        self.frame += 1;
+    }
+
+    /// Returns the number of finished frames.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the number of bytes used by the previous frame.
+    pub fn last_frame_usage(&self) -> usize {
+        self.last_frame_usage
+    }
+
+    /// Returns the largest number of bytes used by a single frame.
+    pub fn high_water_mark(&self) -> usize {
+        self.high_water_mark
+    }
+
+    /// Forget the high-water mark of the previous frames.
+    pub fn reset_high_water_mark(&mut self) {
+        self.high_water_mark = 0;
+    }
+}
@@ -0,0 +1,274 @@
+use crate::c_api::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::UnsafeCell;
+use std::ptr;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+const STATE_UNMAPPED: u8 = 0;
+const STATE_MAPPING: u8 = 1;
+const STATE_MAPPED: u8 = 2;
+const STATE_FAILED: u8 = 3;
+
+/// Describes what happens with an allocation that doesn't fit the region of
+/// a [`RegionGlobalAlloc`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackPolicy {
+    /// Serve the allocation from the system allocator.
+    #[default]
+    System,
+    /// Fail the allocation, the process aborts on allocation failure by default.
+    Fail,
+}
+
+/// Region based allocator that can be installed as the global allocator.
+///
+/// The region is mapped on the first allocation, allocations bump an atomic offset,
+/// so the allocator is lock free. Freeing the most recent allocation rolls the offset
+/// back, any other free is a no-op. Allocations that don't fit the region are handled
+/// according to the [`FallbackPolicy`].
+///
+/// Zeroed allocations only clear the memory that was handed out before,
+/// the rest of the region is still zero filled by the system.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// #[global_allocator]
+/// static GLOBAL: RegionGlobalAlloc = RegionGlobalAlloc::new(64 << 20, FallbackPolicy::System);
+///
+/// let numbers: Vec<u32> = (0..1000).collect();
+///
+/// assert!(GLOBAL.contains(numbers.as_ptr() as *const u8));
+/// assert!(GLOBAL.offset() >= 4000);
+/// ```
+pub struct RegionGlobalAlloc {
+    size: usize,
+    fallback: FallbackPolicy,
+    state: AtomicU8,
+    offset: AtomicUsize,
+    /// The end of the memory that was ever handed out, the memory above is untouched.
+    high_water: AtomicUsize,
+    /// Written once while mapping, read only after the state is `STATE_MAPPED`.
+    region: UnsafeCell<RegionMemoryBuffer>,
+}
+
+// The region is only written before the mapped state is published.
+unsafe impl Sync for RegionGlobalAlloc {}
+
+impl Drop for RegionGlobalAlloc {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        if *self.state.get_mut() == STATE_MAPPED {
+            unsafe { region_memory_buffer_destroy(self.region.get_mut()) };
+        }
+    }
+}
+
+impl RegionGlobalAlloc {
+    /// Create a new allocator with a specific size, the region is mapped on first use.
+    pub const fn new(size: usize, fallback: FallbackPolicy) -> Self {
+        Self {
+            size,
+            fallback,
+            state: AtomicU8::new(STATE_UNMAPPED),
+            offset: AtomicUsize::new(0),
+            high_water: AtomicUsize::new(0),
+            region: UnsafeCell::new(RegionMemoryBuffer {
+                size: 0,
+                base: ptr::null_mut(),
+                offset: 0,
+                committed: 0,
+                initial_commit: 0,
+                owns_memory: false,
+                decommit_on_free: false,
+                poison_pattern: 0,
+                clear_policy: RegionClearPolicy_REGION_CLEAR_KEEP_DATA,
+            }),
+        }
+    }
+
+    /// Returns the number of bytes allocated from the region, including alignment padding.
+    pub fn offset(&self) -> usize {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    /// Returns the size of the region.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the policy for allocations that don't fit the region.
+    pub fn fallback(&self) -> FallbackPolicy {
+        self.fallback
+    }
+
+    /// Returns `true` if `ptr` points into the region.
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let base = self.base() as *const u8;
+        !base.is_null() && base <= ptr && ptr < base.wrapping_add(self.size)
+    }
+
+    /// Returns the base of the region, mapping the region if it's not mapped yet,
+    /// or null if the system failed to map the region.
+    fn map(&self) -> *mut u8 {
+        loop {
+            match self.state.compare_exchange(
+                STATE_UNMAPPED,
+                STATE_MAPPING,
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    // Mapping doesn't allocate, so it can't recurse into the allocator.
+                    let region = unsafe { create_region_memory_buffer(self.size as u64) };
+                    let state = if region.base.is_null() {
+                        STATE_FAILED
+                    } else {
+                        STATE_MAPPED
+                    };
+
+                    unsafe { *self.region.get() = region };
+                    self.state.store(state, Ordering::Release);
+                }
+                Err(STATE_MAPPING) => std::hint::spin_loop(),
+                Err(_) => return self.base(),
+            }
+        }
+    }
+
+    fn base(&self) -> *mut u8 {
+        if self.state.load(Ordering::Acquire) == STATE_MAPPED {
+            unsafe { (*self.region.get()).base }
+        } else {
+            ptr::null_mut()
+        }
+    }
+
+    fn alloc_region(&self, layout: Layout, zeroed: bool) -> *mut u8 {
+        let base = self.map();
+
+        if base.is_null() {
+            return ptr::null_mut();
+        }
+
+        let align = layout.align();
+        let mut offset = self.offset.load(Ordering::Relaxed);
+
+        loop {
+            let start = ((base as usize + offset + align - 1) & !(align - 1)) - base as usize;
+            let end = match start.checked_add(layout.size()) {
+                Some(end) if end <= self.size => end,
+                _ => return ptr::null_mut(),
+            };
+
+            // Synchronizes with the rollback of the previous owner of the memory.
+            match self.offset.compare_exchange_weak(
+                offset,
+                end,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    let data = unsafe { base.add(start) };
+                    let high_water = self.high_water.fetch_max(end, Ordering::Relaxed);
+
+                    if zeroed && high_water > start {
+                        unsafe { data.write_bytes(0, high_water.min(end) - start) };
+                    }
+
+                    return data;
+                }
+                Err(current) => offset = current,
+            }
+        }
+    }
+
+    fn fallback_alloc(&self, layout: Layout, zeroed: bool) -> *mut u8 {
+        match self.fallback {
+            FallbackPolicy::System if zeroed => unsafe { System.alloc_zeroed(layout) },
+            FallbackPolicy::System => unsafe { System.alloc(layout) },
+            FallbackPolicy::Fail => ptr::null_mut(),
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for RegionGlobalAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let data = self.alloc_region(layout, false);
+
+        if data.is_null() {
+            self.fallback_alloc(layout, false)
+        } else {
+            data
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let data = self.alloc_region(layout, true);
+
+        if data.is_null() {
+            self.fallback_alloc(layout, true)
+        } else {
+            data
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if !self.contains(ptr) {
+            System.dealloc(ptr, layout);
+            return;
+        }
+
+        // Only the most recent allocation is rolled back, the alignment padding before it
+        // stays allocated.
+        let start = ptr as usize - self.base() as usize;
+        // Publishes the writes to the memory to its next owner.
+        let _ = self.offset.compare_exchange(
+            start + layout.size(),
+            start,
+            Ordering::Release,
+            Ordering::Relaxed,
+        );
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if !self.contains(ptr) {
+            return System.realloc(ptr, layout, new_size);
+        }
+
+        // The most recent allocation grows or shrinks in place, that both takes memory
+        // from the previous owner and gives memory to the next one.
+        let start = ptr as usize - self.base() as usize;
+
+        if new_size <= self.size - start
+            && self
+                .offset
+                .compare_exchange(
+                    start + layout.size(),
+                    start + new_size,
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            self.high_water
+                .fetch_max(start + new_size, Ordering::Relaxed);
+            return ptr;
+        }
+
+        if new_size <= layout.size() {
+            return ptr;
+        }
+
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let data = self.alloc(new_layout);
+
+        if !data.is_null() {
+            ptr::copy_nonoverlapping(ptr, data, layout.size());
+        }
+
+        data
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{AllocError, RegionAllocator};
+use std::mem;
+use std::ptr;
+
+const NO_SLOT: u32 = u32::MAX;
+
+/// A checked reference to a value of a [`HandlePool`].
+///
+/// The handle is plain data, so it can be stored outside of Rust, e.g. in guest memory.
+/// The `generation` of a slot changes every time its value is removed,
+/// so the pool detects handles that outlived their value.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    /// The index of the slot of the value.
+    pub index: u32,
+    /// The generation of the slot at the moment the value was inserted.
+    pub generation: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    generation: u32,
+    /// The index of the value in the dense array, `NO_SLOT` if the slot is free.
+    dense: u32,
+    /// The next slot in the free list.
+    next_free: u32,
+}
+
+/// Pool of values of a single type reached through generational handles.
+///
+/// Values are stored densely in the region, one after another, a table of slots maps
+/// handles to the values. Removing a value moves the last value into its place,
+/// so iteration only visits live values. Slots of removed values are reused through
+/// a free list, with a new generation that rejects the handles to the removed values.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut pool = HandlePool::new(16).unwrap();
+/// let first = pool.insert(String::from("first")).unwrap();
+/// let second = pool.insert(String::from("second")).unwrap();
+///
+/// pool.get_mut(first).unwrap().push('!');
+/// assert_eq!(Ok("first!"), pool.get(first).map(String::as_str));
+///
+/// assert_eq!(Ok(String::from("first!")), pool.remove(first));
+/// assert_eq!(Err(AllocError::StaleHandle), pool.get(first));
+///
+/// // The slot is reused with a new generation.
+/// let third = pool.insert(String::from("third")).unwrap();
+/// assert_eq!(first.index, third.index);
+/// assert_ne!(first.generation, third.generation);
+///
+/// let values: Vec<_> = pool.iter().map(|(_, value)| value.as_str()).collect();
+/// assert_eq!(vec!["second", "third"], values);
+/// assert_eq!(Some(second), pool.iter().map(|(handle, _)| handle).next());
+/// ```
+pub struct HandlePool<T> {
+    /// Owns the memory of the values and the slot arrays, that are only reached
+    /// through their pointers.
+    _allocator: RegionAllocator,
+    /// The dense array of values, the base of the region can be padded to the alignment of `T`.
+    values: *mut T,
+    /// The index of the slot of every value in the dense array.
+    dense_slots: *mut u32,
+    slots: *mut Slot,
+    capacity: usize,
+    len: usize,
+    /// The number of slots that were used at least once.
+    slots_len: usize,
+    free_slot: u32,
+}
+
+impl<T> HandlePool<T> {
+    /// Create a new pool that fits `capacity` values.
+    ///
+    /// # Errors
+    ///
+    /// If the size of the pool can't be mapped on this platform or the system fails
+    /// to map the memory, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// #[repr(align(8192))]
+    /// struct Page(u32);
+    ///
+    /// let mut pool = HandlePool::new(2).unwrap();
+    /// let first = pool.insert(Page(1)).unwrap();
+    /// let second = pool.insert(Page(2)).unwrap();
+    ///
+    /// assert_eq!(0, pool.get(first).unwrap() as *const Page as usize % 8192);
+    /// assert_eq!(0, pool.get(second).unwrap() as *const Page as usize % 8192);
+    ///
+    /// // Removing the first value moves the second one into its place.
+    /// assert_eq!(1, pool.remove(first).unwrap().0);
+    /// assert_eq!(2, pool.get(second).unwrap().0);
+    /// ```
+    pub fn new(capacity: usize) -> Result<Self, AllocError> {
+        if capacity >= NO_SLOT as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        // Every array is aligned separately, so reserve space for the padding as well.
+        let size = [
+            mem::size_of::<T>(),
+            mem::size_of::<u32>(),
+            mem::size_of::<Slot>(),
+        ]
+        .iter()
+        .try_fold(0usize, |size, item_size| {
+            capacity
+                .checked_mul(*item_size)
+                .and_then(|array_size| size.checked_add(array_size))
+        })
+        .and_then(|size| size.checked_add(mem::align_of::<T>() + mem::align_of::<Slot>()))
+        .ok_or(AllocError::RegionTooLarge)?;
+
+        let allocator = RegionAllocator::new(size)?;
+        let values =
+            allocator.alloc_aligned(capacity * mem::size_of::<T>(), mem::align_of::<T>())?;
+        let dense_slots =
+            allocator.alloc_aligned(capacity * mem::size_of::<u32>(), mem::align_of::<u32>())?;
+        let slots =
+            allocator.alloc_aligned(capacity * mem::size_of::<Slot>(), mem::align_of::<Slot>())?;
+
+        Ok(Self {
+            _allocator: allocator,
+            values: values as *mut T,
+            dense_slots: dense_slots as *mut u32,
+            slots: slots as *mut Slot,
+            capacity,
+            len: 0,
+            slots_len: 0,
+            free_slot: NO_SLOT,
+        })
+    }
+
+    /// Move the `value` to the pool.
+    ///
+    /// Returns a handle to the value.
+    ///
+    /// # Errors
+    ///
+    /// If the pool is full, then this call will return an error.
+    pub fn insert(&mut self, value: T) -> Result<Handle, AllocError> {
+        if self.len == self.capacity {
+            return Err(AllocError::OutOfMemory {
+                requested: mem::size_of::<T>(),
+                available: 0,
+            });
+        }
+
+        let index = if self.free_slot != NO_SLOT {
+            let index = self.free_slot;
+            self.free_slot = self.slot(index).next_free;
+            index
+        } else {
+            self.slots_len += 1;
+            let index = (self.slots_len - 1) as u32;
+
+            unsafe {
+                self.slots.add(index as usize).write(Slot {
+                    generation: 0,
+                    dense: NO_SLOT,
+                    next_free: NO_SLOT,
+                })
+            };
+
+            index
+        };
+
+        unsafe {
+            self.values_ptr().add(self.len).write(value);
+            self.dense_slots.add(self.len).write(index);
+        }
+
+        let dense = self.len as u32;
+        let slot = self.slot_mut(index);
+        slot.dense = dense;
+        let generation = slot.generation;
+
+        self.len += 1;
+        Ok(Handle { index, generation })
+    }
+
+    /// Returns a reference to the value of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the value of the `handle` was removed, then this call will return an error.
+    pub fn get(&self, handle: Handle) -> Result<&T, AllocError> {
+        let dense = self.dense_index(handle)?;
+        Ok(unsafe { &*self.values_ptr().add(dense) })
+    }
+
+    /// Returns a mutable reference to the value of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the value of the `handle` was removed, then this call will return an error.
+    pub fn get_mut(&mut self, handle: Handle) -> Result<&mut T, AllocError> {
+        let dense = self.dense_index(handle)?;
+        Ok(unsafe { &mut *self.values_ptr().add(dense) })
+    }
+
+    /// Returns `true` if the value of the `handle` is in the pool.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.dense_index(handle).is_ok()
+    }
+
+    /// Remove the value of the `handle` from the pool and return it.
+    ///
+    /// The last value of the pool takes the place of the removed value.
+    ///
+    /// # Errors
+    ///
+    /// If the value of the `handle` was already removed, then this call will return an error.
+    pub fn remove(&mut self, handle: Handle) -> Result<T, AllocError> {
+        let dense = self.dense_index(handle)?;
+        let last = self.len - 1;
+        self.len = last;
+
+        let value = unsafe {
+            let value = self.values_ptr().add(dense).read();
+
+            if dense != last {
+                let moved_slot = self.dense_slots.add(last).read();
+
+                ptr::copy_nonoverlapping(
+                    self.values_ptr().add(last),
+                    self.values_ptr().add(dense),
+                    1,
+                );
+                self.dense_slots.add(dense).write(moved_slot);
+                self.slot_mut(moved_slot).dense = dense as u32;
+            }
+
+            value
+        };
+
+        self.free(handle.index);
+        Ok(value)
+    }
+
+    /// Returns an iterator over the live values and their handles.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> + '_ {
+        self.dense_slots()
+            .iter()
+            .zip(self.values())
+            .map(move |(&index, value)| (self.handle(index), value))
+    }
+
+    /// Returns an iterator over the live values and their handles,
+    /// that allows modifying the values.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> + '_ {
+        let values = unsafe { std::slice::from_raw_parts_mut(self.values_ptr(), self.len) };
+        let this = &*self;
+
+        this.dense_slots()
+            .iter()
+            .zip(values)
+            .map(move |(&index, value)| (this.handle(index), value))
+    }
+
+    /// Returns the live values as a slice, in no particular order.
+    pub fn values(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.values_ptr(), self.len) }
+    }
+
+    /// Returns the live values as a mutable slice, in no particular order.
+    pub fn values_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.values_ptr(), self.len) }
+    }
+
+    /// Returns the number of values the pool fits.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values in the pool.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the pool contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Drop all values, the handles to the values become stale.
+    pub fn clear(&mut self) {
+        while self.len > 0 {
+            // Shrink first, so a panicking destructor doesn't lead to a double drop.
+            self.len -= 1;
+            let index = unsafe { self.dense_slots.add(self.len).read() };
+            self.free(index);
+            unsafe { ptr::drop_in_place(self.values_ptr().add(self.len)) };
+        }
+    }
+
+    fn dense_index(&self, handle: Handle) -> Result<usize, AllocError> {
+        if handle.index as usize >= self.slots_len {
+            return Err(AllocError::StaleHandle);
+        }
+
+        let slot = self.slot(handle.index);
+
+        if slot.generation != handle.generation || slot.dense == NO_SLOT {
+            Err(AllocError::StaleHandle)
+        } else {
+            Ok(slot.dense as usize)
+        }
+    }
+
+    /// Put the slot to the free list with a new generation.
+    fn free(&mut self, index: u32) {
+        let free_slot = self.free_slot;
+        let slot = self.slot_mut(index);
+
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.dense = NO_SLOT;
+        slot.next_free = free_slot;
+
+        self.free_slot = index;
+    }
+
+    fn handle(&self, index: u32) -> Handle {
+        Handle {
+            index,
+            generation: self.slot(index).generation,
+        }
+    }
+
+    fn values_ptr(&self) -> *mut T {
+        self.values
+    }
+
+    fn dense_slots(&self) -> &[u32] {
+        unsafe { std::slice::from_raw_parts(self.dense_slots, self.len) }
+    }
+
+    fn slot(&self, index: u32) -> &Slot {
+        unsafe { &*self.slots.add(index as usize) }
+    }
+
+    fn slot_mut(&mut self, index: u32) -> &mut Slot {
+        unsafe { &mut *self.slots.add(index as usize) }
+    }
+}
+
+impl<T> Drop for HandlePool<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
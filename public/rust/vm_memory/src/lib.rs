@@ -1,9 +1,55 @@
+mod arena_handle;
+mod buddy;
 #[allow(warnings)]
 #[allow(clippy::all)]
 mod c_api;
+mod chained_region;
+mod clear_policy;
+mod compacting_arena;
+mod double_ended_region;
+mod error;
+mod frame;
+mod global_alloc;
+mod handle_pool;
+mod marker;
+mod pool;
+mod ring;
+mod slab;
+mod stack;
+mod sub_region;
+mod tlsf;
+mod typed_arena;
 
+pub use arena_handle::ArenaHandle;
+pub use buddy::{BuddyAllocator, BuddyStats};
 use c_api::*;
+pub use chained_region::{ChainedRegionAllocator, DEFAULT_GROWTH_FACTOR};
+pub use clear_policy::ClearPolicy;
+pub use compacting_arena::CompactingArena;
+pub use double_ended_region::DoubleEndedRegion;
+pub use error::AllocError;
+pub use frame::FrameAllocator;
+pub use global_alloc::{FallbackPolicy, RegionGlobalAlloc};
+pub use handle_pool::{Handle, HandlePool};
+pub use marker::{RegionMarker, ScopedRegion};
+pub use pool::{Pool, PoolAllocator, PoolBox};
+pub use ring::{RingAllocator, RingMarker};
+pub use slab::{SlabAllocator, SLAB_SIZE_CLASSES};
+pub use stack::StackAllocator;
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::io;
+use std::marker::PhantomData;
 use std::mem;
+use std::slice;
+use std::str;
+use std::sync::atomic::{AtomicU64, Ordering};
+pub use sub_region::SubRegionAllocator;
+pub use tlsf::TlsfAllocator;
+pub use typed_arena::TypedArena;
+
+/// Alignment used for untyped buffers, matches the alignment guaranteed by `malloc`.
+pub const DEFAULT_ALIGNMENT: usize = 16;
 
 /// Accessing to allocated buffer
 pub trait BufferAccessor {
@@ -17,29 +63,201 @@ pub trait BufferAccessor {
 /// Allocates continuous chunk of memory with a specific size.
 /// Allocator maintain a pointer within that memory, whenever allocate an object,
 /// update the pointer by the object's size.
+/// The memory is released back to the system when the allocator is dropped.
+///
+/// Allocation only needs a shared reference to the allocator, the returned references
+/// borrow the allocator, so the borrow checker rejects any use of them after [`clear`]:
+///
+/// ```compile_fail
+/// use vm_memory::*;
+///
+/// let mut allocator = RegionAllocator::new(1024).unwrap();
+/// let data = allocator.emplace_str("hello").unwrap();
+/// allocator.clear();
+///
+/// assert_eq!("hello", data);
+/// ```
+///
+/// [`clear`]: RegionAllocator::clear
 pub struct RegionAllocator {
     /// The memory reserved for the allocator.
-    pub region: RegionMemoryBuffer,
+    region: Cell<RegionMemoryBuffer>,
+    /// Changed on every clear and reset to detect stale handles.
+    generation: u64,
+    /// The lowest offset the region was cleared or reset to since the innermost
+    /// [`ScopedRegion`] was created.
+    pub(crate) reset_floor: usize,
 }
 
 impl BufferAccessor for RegionAllocator {
     fn get_buffer_ptr(&self) -> *mut u8 {
-        self.region.base
+        self.region.get().base
     }
 
     fn get_buffer_size(&self) -> u64 {
-        self.region.size
+        self.region.get().size
+    }
+}
+
+impl Drop for RegionAllocator {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        unsafe { region_memory_buffer_destroy(self.region.get_mut()) };
     }
 }
 
 impl RegionAllocator {
     /// Create a new allocator with a specific size.
-    pub fn new(size: usize) -> Self {
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// assert_eq!(1024, allocator.get_buffer_size());
+    ///
+    /// // An empty region only fits zero sized allocations.
+    /// let empty = RegionAllocator::new(0).unwrap();
+    /// assert!(empty.alloc(0).is_ok());
+    /// assert!(empty.alloc(1).is_err());
+    /// ```
+    ///
+    /// Regions larger than 4 GiB are supported, physical pages are only backed on first touch:
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let size = 5 << 30;
+    /// let allocator = RegionAllocator::new(size).unwrap();
+    /// assert_eq!(size as u64, allocator.get_buffer_size());
+    ///
+    /// allocator.alloc(size - 16).unwrap();
+    /// let top = allocator.alloc_aligned(16, 16).unwrap();
+    /// unsafe { top.write_bytes(0xAB, 16) };
+    ///
+    /// assert_eq!(size, allocator.offset());
+    /// assert_eq!(0xAB, unsafe { *top.add(15) });
+    /// assert!(allocator.alloc(1).is_err());
+    /// ```
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        if size > isize::MAX as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let region = unsafe { create_region_memory_buffer(size as u64) };
+
+        if region.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else {
+            Ok(Self::from_region(region))
+        }
+    }
+
+    /// Create a new growable allocator that reserves `max` bytes of address space
+    /// and commits `initial_commit` bytes of it.
+    ///
+    /// The reserved memory is not accessible until it is committed, pages are committed
+    /// as allocations cross page boundaries, so the region grows without moving.
+    ///
+    /// # Errors
+    ///
+    /// If the `max` can't be reserved on this platform or the system fails to reserve
+    /// or commit the memory, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::reserve(64 << 30, 4096).unwrap();
+    /// assert_eq!(64 << 30, allocator.get_buffer_size());
+    /// assert_eq!(4096, allocator.committed_size());
+    ///
+    /// let base = allocator.alloc(1 << 20).unwrap();
+    /// unsafe { base.write_bytes(0xAB, 1 << 20) };
+    /// assert_eq!(1 << 20, allocator.committed_size());
+    ///
+    /// allocator.set_decommit_on_clear(true);
+    /// allocator.clear();
+    /// assert_eq!(4096, allocator.committed_size());
+    /// ```
+    pub fn reserve(max: usize, initial_commit: usize) -> Result<Self, AllocError> {
+        if max > isize::MAX as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let region = unsafe { reserve_region_memory_buffer(max as u64, initial_commit as u64) };
+
+        if region.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else {
+            Ok(Self::from_region(region))
+        }
+    }
+
+    fn from_region(region: RegionMemoryBuffer) -> Self {
         Self {
-            region: unsafe { create_region_memory_buffer(size as u64) },
+            region: Cell::new(region),
+            generation: next_generation(),
+            reset_floor: usize::MAX,
         }
     }
 
+    /// Returns the number of bytes allocated from the region, including alignment padding.
+    pub fn offset(&self) -> usize {
+        self.region.get().offset
+    }
+
+    /// Returns the number of bytes of the region that are backed by accessible memory.
+    pub fn committed_size(&self) -> usize {
+        self.region.get().committed as usize
+    }
+
+    /// When enabled, [`clear`](Self::clear) gives the pages committed beyond
+    /// the initial commit back to the system.
+    pub fn set_decommit_on_clear(&mut self, decommit: bool) {
+        self.region.get_mut().decommit_on_free = decommit;
+    }
+
+    /// Returns what [`clear`](Self::clear) does with the used memory.
+    pub fn clear_policy(&self) -> ClearPolicy {
+        ClearPolicy::from_region(&self.region.get())
+    }
+
+    /// Set what [`clear`](Self::clear) does with the used memory,
+    /// [`ClearPolicy::KeepData`] by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::new(8192).unwrap();
+    /// allocator.set_clear_policy(ClearPolicy::Poison(0xCD));
+    ///
+    /// let data = allocator.alloc(16).unwrap();
+    /// unsafe { data.write_bytes(0xAB, 16) };
+    /// allocator.clear();
+    /// assert_eq!(0xCD, unsafe { *data });
+    ///
+    /// allocator.set_clear_policy(ClearPolicy::Release);
+    /// let data = allocator.alloc(8192).unwrap();
+    /// unsafe { data.write_bytes(0xAB, 8192) };
+    /// allocator.clear();
+    /// assert_eq!(0, unsafe { *data });
+    /// ```
+    pub fn set_clear_policy(&mut self, policy: ClearPolicy) {
+        policy.apply(self.region.get_mut());
+    }
+
     /// Allocate a new chunk of memory with a specific size.
     /// returns the base address of the allocated chunk of memory.
     ///
@@ -52,32 +270,426 @@ impl RegionAllocator {
     /// ```
     /// use vm_memory::*;
     ///
-    /// let mut allocator = RegionAllocator::new(1024);
+    /// let allocator = RegionAllocator::new(1024).unwrap();
     /// let base = allocator.alloc(512);
     /// assert!(base.is_ok());
+    ///
+    /// let requested = 1024;
+    /// let available = 512;
+    /// assert_eq!(
+    ///     Err(AllocError::OutOfMemory { requested, available }),
+    ///     allocator.alloc(1024)
+    /// );
     /// ```
-    pub fn alloc(&mut self, size: usize) -> Result<*mut u8, &'static str> {
+    pub fn alloc(&self, size: usize) -> Result<*mut u8, AllocError> {
+        let data = unsafe { region_memory_buffer_alloc(self.region.as_ptr(), size as u64) };
+
+        if data.is_null() {
+            Err(self.out_of_memory(size))
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Allocate a new chunk of memory with a specific size, the returned address
+    /// is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out or `align` is not a power of two,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(3).unwrap();
+    /// let base = allocator.alloc_aligned(8, 8).unwrap();
+    ///
+    /// assert_eq!(0, base as usize % 8);
+    /// assert_eq!(16, allocator.offset());
+    /// assert_eq!(Err(AllocError::InvalidAlignment), allocator.alloc_aligned(8, 3));
+    /// ```
+    pub fn alloc_aligned(&self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
         let data = unsafe {
-            region_memory_buffer_alloc(&mut self.region as *mut RegionMemoryBuffer, size as u64)
+            region_memory_buffer_alloc_aligned(self.region.as_ptr(), size as u64, align as u64)
         };
 
         if data.is_null() {
-            Err("Out of memory")
+            Err(self.out_of_memory(size))
         } else {
             Ok(data)
         }
     }
 
-    /// Free all memory.
-    pub fn clear(&mut self) -> Result<(), &'static str> {
-        unsafe { region_memory_buffer_free(&mut self.region as *mut RegionMemoryBuffer) };
-        Ok(())
+    /// Allocate a new chunk of memory that fits the `layout`.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::alloc::Layout;
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(1).unwrap();
+    /// let base = allocator.alloc_layout(Layout::new::<u32>()).unwrap();
+    ///
+    /// assert_eq!(0, base as usize % 4);
+    /// assert_eq!(8, allocator.offset());
+    /// ```
+    pub fn alloc_layout(&self, layout: Layout) -> Result<*mut u8, AllocError> {
+        self.alloc_aligned(layout.size(), layout.align())
+    }
+
+    /// Change the size of the allocation at `ptr` from `old_size` to `new_size`.
+    ///
+    /// The most recent allocation grows or shrinks in place, any other allocation
+    /// shrinks in place or is moved to a new chunk of memory when grows.
+    /// Returns the address of the reallocated memory, the contents are preserved
+    /// up to the smaller of the sizes. A null `ptr` makes a new allocation.
+    ///
+    /// The moved memory is aligned to [`DEFAULT_ALIGNMENT`], use
+    /// [`realloc_aligned`](Self::realloc_aligned) for allocations with a larger alignment.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` should be null or returned by this allocator with `old_size` bytes,
+    /// the memory past `new_size` must not be used after the call.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.alloc(16).unwrap();
+    /// unsafe { data.write_bytes(0xAB, 16) };
+    ///
+    /// let grown = unsafe { allocator.realloc(data, 16, 64).unwrap() };
+    /// assert_eq!(data, grown);
+    /// assert_eq!(64, allocator.offset());
+    ///
+    /// let shrunk = unsafe { allocator.realloc(grown, 64, 32).unwrap() };
+    /// assert_eq!(data, shrunk);
+    /// assert_eq!(32, allocator.offset());
+    ///
+    /// allocator.alloc(1).unwrap();
+    /// let moved = unsafe { allocator.realloc(shrunk, 32, 48).unwrap() };
+    /// assert_ne!(data, moved);
+    /// assert_eq!(0, moved as usize % DEFAULT_ALIGNMENT);
+    /// assert_eq!(0xAB, unsafe { *moved.add(15) });
+    /// assert_eq!(96, allocator.offset());
+    /// ```
+    pub unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        old_size: usize,
+        new_size: usize,
+    ) -> Result<*mut u8, AllocError> {
+        self.realloc_aligned(ptr, old_size, new_size, DEFAULT_ALIGNMENT)
+    }
+
+    /// Change the size of the allocation at `ptr` from `old_size` to `new_size`,
+    /// the moved memory is aligned to `align` bytes.
+    /// See [`realloc`](Self::realloc).
+    ///
+    /// # Safety
+    ///
+    /// `ptr` should be null or returned by this allocator with `old_size` bytes,
+    /// the memory past `new_size` must not be used after the call.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out or `align` is not a power of two,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.alloc_aligned(16, 64).unwrap();
+    /// allocator.alloc(1).unwrap();
+    ///
+    /// let moved = unsafe { allocator.realloc_aligned(data, 16, 32, 64).unwrap() };
+    /// assert_ne!(data, moved);
+    /// assert_eq!(0, moved as usize % 64);
+    /// ```
+    pub unsafe fn realloc_aligned(
+        &self,
+        ptr: *mut u8,
+        old_size: usize,
+        new_size: usize,
+        align: usize,
+    ) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let data = region_memory_buffer_realloc(
+            self.region.as_ptr(),
+            ptr,
+            old_size as u64,
+            new_size as u64,
+            align as u64,
+        );
+
+        if data.is_null() {
+            Err(self.out_of_memory(new_size))
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Returns a marker of the current position in the region.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(128).unwrap();
+    ///
+    /// assert_eq!(128, allocator.marker().offset());
+    /// ```
+    pub fn marker(&self) -> RegionMarker {
+        RegionMarker(unsafe { region_memory_buffer_get_marker(self.region.as_ptr()) })
+    }
+
+    /// Free every allocation made after the `marker` was taken.
+    ///
+    /// The generation of the allocator changes, so every handle made by
+    /// [`alloc_handle`](Self::alloc_handle) becomes stale, including the ones below the `marker`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region is already below the `marker`, e.g. it was cleared
+    /// or reset to an earlier marker.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(128).unwrap();
+    ///
+    /// let marker = allocator.marker();
+    /// let handle = allocator.alloc_handle(String::from("text")).unwrap();
+    /// allocator.reset_to(marker);
+    ///
+    /// assert_eq!(128, allocator.offset());
+    ///
+    /// // The memory of the value is reused, the handle doesn't reach it anymore.
+    /// allocator.emplace([0xAAu8; 24]).unwrap();
+    /// assert_eq!(Err(AllocError::StaleHandle), allocator.get(&handle));
+    /// ```
+    pub fn reset_to(&mut self, marker: RegionMarker) {
+        assert!(
+            marker.0 <= self.offset(),
+            "marker is above the current offset of the region"
+        );
+
+        unsafe { region_memory_buffer_reset_to_marker(self.region.get_mut(), marker.0) };
+        self.generation = next_generation();
+        self.reset_floor = self.reset_floor.min(marker.0);
+    }
+
+    /// Returns a guard for scratch allocations, every allocation made through the guard
+    /// is freed when the guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(128).unwrap();
+    ///
+    /// let handle = {
+    ///     let mut scratch = allocator.scoped();
+    ///     scratch.alloc(256).unwrap();
+    ///     assert_eq!(384, scratch.offset());
+    ///     scratch.alloc_handle(0xDEADBEEFu64).unwrap()
+    /// };
+    ///
+    /// assert_eq!(128, allocator.offset());
+    ///
+    /// // Allocations of the scope are freed, so are their handles.
+    /// allocator.emplace(0u64).unwrap();
+    /// assert_eq!(Err(AllocError::StaleHandle), allocator.get(&handle));
+    /// ```
+    ///
+    /// Clearing the allocator inside the scope frees the memory below the scope as well,
+    /// the allocations made after the clear are kept then:
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::new(1024).unwrap();
+    /// allocator.alloc(128).unwrap();
+    ///
+    /// {
+    ///     let mut scratch = allocator.scoped();
+    ///     scratch.clear();
+    ///     scratch.alloc(256).unwrap();
+    /// }
+    ///
+    /// assert_eq!(256, allocator.offset());
+    /// ```
+    pub fn scoped(&mut self) -> ScopedRegion<'_> {
+        ScopedRegion::new(self)
+    }
+
+    /// Carve a sub-region with a specific size out of the memory of the allocator.
+    ///
+    /// The sub-region has its own allocation offset and borrows the allocator,
+    /// that allows to hand out memory budgets from a single mapping.
+    /// The allocator can't be cleared while the sub-region is alive.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let mut sub_region = allocator.split_off(256).unwrap();
+    ///
+    /// sub_region.alloc(200).unwrap();
+    /// assert!(sub_region.alloc(100).is_err());
+    ///
+    /// sub_region.clear();
+    /// sub_region.alloc(256).unwrap();
+    ///
+    /// assert_eq!(256, allocator.offset());
+    /// assert!(allocator.split_off(1024).is_err());
+    /// ```
+    pub fn split_off(&self, size: usize) -> Result<SubRegionAllocator<'_>, AllocError> {
+        let region =
+            unsafe { region_memory_buffer_emplace_region(self.region.as_ptr(), size as u64) };
+
+        if region.base.is_null() {
+            Err(self.out_of_memory(size))
+        } else {
+            Ok(SubRegionAllocator::new(RegionAllocator::from_region(
+                region,
+            )))
+        }
+    }
+
+    /// Free all memory, the used memory is handled according to the [`ClearPolicy`].
+    pub fn clear(&mut self) {
+        unsafe { region_memory_buffer_free(self.region.get_mut()) };
+        self.generation = next_generation();
+        self.reset_floor = 0;
     }
 
-    /// Allocate a new region of memory with size equals to size of `T` and emplace the `value`
+    /// Returns the current generation of the allocator.
+    ///
+    /// Generations are unique across all allocators, a new one is assigned on every clear
+    /// and every reset to a marker.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Allocate a new region of memory with size and alignment of `T` and move the `value`
     /// to the allocated memory.
     ///
-    /// Returns a pointer to the struct located in the memory of the allocator.
+    /// Returns a handle to the value, that is checked against the generation
+    /// of the allocator on every access.
+    /// The value is never dropped, the memory is reused after [`clear`](Self::clear).
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = RegionAllocator::new(1024).unwrap();
+    /// let handle = allocator.alloc_handle(12u32).unwrap();
+    ///
+    /// *allocator.get_mut(&handle).unwrap() += 1;
+    /// assert_eq!(Ok(&13), allocator.get(&handle));
+    ///
+    /// allocator.clear();
+    /// assert_eq!(Err(AllocError::StaleHandle), allocator.get(&handle));
+    ///
+    /// let other = RegionAllocator::new(1024).unwrap();
+    /// other.alloc(64).unwrap();
+    /// assert_eq!(Err(AllocError::StaleHandle), other.get(&handle));
+    /// ```
+    pub fn alloc_handle<T>(&self, value: T) -> Result<ArenaHandle<T>, AllocError> {
+        let data = self.emplace(value)? as *mut T;
+
+        Ok(ArenaHandle {
+            offset: data as usize - self.get_buffer_ptr() as usize,
+            generation: self.generation,
+            value: PhantomData,
+        })
+    }
+
+    /// Returns a reference to the value of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the allocator was cleared or reset to a marker since the handle was created,
+    /// then this call will return an error.
+    pub fn get<T>(&self, handle: &ArenaHandle<T>) -> Result<&T, AllocError> {
+        self.handle_ptr(handle).map(|data| unsafe { &*data })
+    }
+
+    /// Returns a mutable reference to the value of the `handle`.
+    ///
+    /// # Errors
+    ///
+    /// If the allocator was cleared or reset to a marker since the handle was created,
+    /// then this call will return an error.
+    pub fn get_mut<T>(&mut self, handle: &ArenaHandle<T>) -> Result<&mut T, AllocError> {
+        self.handle_ptr(handle).map(|data| unsafe { &mut *data })
+    }
+
+    fn handle_ptr<T>(&self, handle: &ArenaHandle<T>) -> Result<*mut T, AllocError> {
+        let end = handle.offset + mem::size_of::<T>();
+
+        // Shrinking the last allocation frees the value without changing the generation.
+        if handle.generation != self.generation || end > self.offset() {
+            Err(AllocError::StaleHandle)
+        } else {
+            Ok(unsafe { self.get_buffer_ptr().add(handle.offset) } as *mut T)
+        }
+    }
+
+    /// Allocate a new region of memory with size and alignment of `T` and emplace the `value`
+    /// to the allocated memory.
+    ///
+    /// Returns a reference to the struct located in the memory of the allocator.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
     ///
     /// # Examples
     ///
@@ -85,33 +697,69 @@ impl RegionAllocator {
     /// use std::mem;
     /// use vm_memory::*;
     ///
-    /// let mut allocator = RegionAllocator::new(1024);
+    /// let allocator = RegionAllocator::new(1024).unwrap();
     /// let data: i32 = 12;
-    /// let data: &i32 = unsafe { allocator.emplace_struct(&data).unwrap().as_ref().unwrap() };
+    /// let data = allocator.emplace_struct(&data).unwrap();
+    /// let other = allocator.emplace_struct(&24).unwrap();
+    /// *data += 1;
     ///
-    /// assert_eq!(mem::size_of::<i32>(), allocator.region.offset);
-    /// assert_eq!(12, *data);
+    /// assert_eq!(2 * mem::size_of::<i32>(), allocator.offset());
+    /// assert_eq!(13, *data);
+    /// assert_eq!(24, *other);
     /// ```
-    pub fn emplace_struct<T>(&mut self, value: &T) -> Result<*mut T, &'static str> {
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_struct<T: Copy>(&self, value: &T) -> Result<&mut T, AllocError> {
         let value_ptr = value as *const T;
         let data = unsafe {
-            region_memory_buffer_emplace(
-                &mut self.region as *mut RegionMemoryBuffer,
+            region_memory_buffer_emplace_aligned(
+                self.region.as_ptr(),
                 mem::size_of::<T>() as u64,
+                mem::align_of::<T>() as u64,
                 value_ptr as *const u8,
             )
         };
 
         if data.is_null() {
-            Err("Out of memory")
+            Err(self.out_of_memory(mem::size_of::<T>()))
         } else {
-            Ok(data as *mut T)
+            Ok(unsafe { &mut *(data as *mut T) })
         }
     }
 
-    /// Allocate a new region of memory with size equals to `size` and emplace the `base`
+    /// Allocate a new region of memory with size and alignment of `T` and move the `value`
     /// to the allocated memory.
     ///
+    /// Returns a reference to the value located in the memory of the allocator.
+    /// The value is never dropped, the memory is reused after [`clear`](Self::clear).
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.emplace([1u8, 2, 3]).unwrap();
+    /// data[0] = 4;
+    ///
+    /// assert_eq!([4, 2, 3], *data);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace<T>(&self, value: T) -> Result<&mut T, AllocError> {
+        let data = self.alloc_aligned(mem::size_of::<T>(), mem::align_of::<T>())? as *mut T;
+
+        unsafe {
+            data.write(value);
+            Ok(&mut *data)
+        }
+    }
+
+    /// Allocate a new region of memory with size equals to `size` and emplace the `base`
+    /// to the allocated memory. The emplaced buffer is aligned to [`DEFAULT_ALIGNMENT`].
+    ///
     /// Returns a pointer to the base pointer located in the memory of the allocator.
     ///
     /// # Safety
@@ -124,7 +772,7 @@ impl RegionAllocator {
     /// use std::mem;
     /// use vm_memory::*;
     ///
-    /// let mut allocator = RegionAllocator::new(1024);
+    /// let allocator = RegionAllocator::new(1024).unwrap();
     /// let data: i32 = 12;
     /// let data_ptr = &data as *const i32;
     /// let size = mem::size_of::<i32>() as u64;
@@ -136,21 +784,141 @@ impl RegionAllocator {
     ///
     /// let data = unsafe { *(data_emplaced_ptr as *mut i32) };
     ///
-    /// assert_eq!(size as usize, allocator.region.offset);
+    /// assert_eq!(size as usize, allocator.offset());
     /// assert_eq!(12, data);
     /// ```
-    pub unsafe fn emplace_buffer(
-        &mut self,
-        base: *const u8,
-        size: u64,
-    ) -> Result<*mut u8, &'static str> {
-        let data =
-            region_memory_buffer_emplace(&mut self.region as *mut RegionMemoryBuffer, size, base);
+    pub unsafe fn emplace_buffer(&self, base: *const u8, size: u64) -> Result<*mut u8, AllocError> {
+        let data = region_memory_buffer_emplace_aligned(
+            self.region.as_ptr(),
+            size,
+            DEFAULT_ALIGNMENT as u64,
+            base,
+        );
 
         if data.is_null() {
-            Err("Out of memory")
+            Err(self.out_of_memory(size as usize))
         } else {
             Ok(data)
         }
     }
+
+    /// Allocate a new region of memory that fits the `values` and copy the `values`
+    /// to the allocated memory.
+    ///
+    /// Returns a slice located in the memory of the allocator.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.emplace_slice(&[1u32, 2, 3]).unwrap();
+    /// data[0] = 4;
+    ///
+    /// assert_eq!(&[4, 2, 3], data);
+    /// assert_eq!(12, allocator.offset());
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_slice<T: Copy>(&self, values: &[T]) -> Result<&mut [T], AllocError> {
+        let size = mem::size_of_val(values);
+        let data = unsafe {
+            region_memory_buffer_emplace_aligned(
+                self.region.as_ptr(),
+                size as u64,
+                mem::align_of::<T>() as u64,
+                values.as_ptr() as *const u8,
+            )
+        };
+
+        if data.is_null() {
+            Err(self.out_of_memory(size))
+        } else {
+            Ok(unsafe { slice::from_raw_parts_mut(data as *mut T, values.len()) })
+        }
+    }
+
+    /// Allocate a new region of memory that fits the `value` and copy the `value`
+    /// to the allocated memory.
+    ///
+    /// Returns a string located in the memory of the allocator.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.emplace_str("hello").unwrap();
+    /// data.make_ascii_uppercase();
+    ///
+    /// assert_eq!("HELLO", data);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_str(&self, value: &str) -> Result<&mut str, AllocError> {
+        let data = self.emplace_slice(value.as_bytes())?;
+        Ok(unsafe { str::from_utf8_unchecked_mut(data) })
+    }
+
+    /// Allocate a new region of memory that fits all items of the `iter` and move
+    /// the items to the allocated memory.
+    ///
+    /// Returns a slice located in the memory of the allocator.
+    /// The items are never dropped, the memory is reused after [`clear`](Self::clear).
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = RegionAllocator::new(1024).unwrap();
+    /// let data = allocator.emplace_iter((1..5).map(|x| x * x)).unwrap();
+    ///
+    /// assert_eq!(&[1, 4, 9, 16], data);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn emplace_iter<I>(&self, iter: I) -> Result<&mut [I::Item], AllocError>
+    where
+        I: IntoIterator,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let len = iter.len();
+        let size = len.saturating_mul(mem::size_of::<I::Item>());
+        let data = self.alloc_aligned(size, mem::align_of::<I::Item>())? as *mut I::Item;
+        let mut count = 0;
+
+        // Don't trust the reported length, never write past the allocated memory
+        // and only expose the items that were actually written.
+        for item in iter.take(len) {
+            unsafe { data.add(count).write(item) };
+            count += 1;
+        }
+
+        Ok(unsafe { slice::from_raw_parts_mut(data, count) })
+    }
+
+    fn out_of_memory(&self, requested: usize) -> AllocError {
+        AllocError::OutOfMemory {
+            requested,
+            available: self.get_buffer_size() as usize - self.offset(),
+        }
+    }
+}
+
+fn next_generation() -> u64 {
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    GENERATION.fetch_add(1, Ordering::Relaxed)
 }
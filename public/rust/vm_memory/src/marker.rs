@@ -0,0 +1,70 @@
+use crate::RegionAllocator;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// A position inside the region of a [`RegionAllocator`].
+///
+/// Resetting the allocator to a marker frees every allocation made after the marker was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RegionMarker(pub(crate) usize);
+
+impl RegionMarker {
+    /// Returns the offset of the marker from the base of the region.
+    pub fn offset(&self) -> usize {
+        self.0
+    }
+}
+
+/// Guard that frees every allocation made through it when dropped.
+///
+/// Created by [`RegionAllocator::scoped`], dereferences to the allocator itself.
+pub struct ScopedRegion<'a> {
+    allocator: &'a mut RegionAllocator,
+    marker: RegionMarker,
+    /// The reset floor of the enclosing scope, restored when the guard is dropped.
+    outer_reset_floor: usize,
+}
+
+impl<'a> ScopedRegion<'a> {
+    pub(crate) fn new(allocator: &'a mut RegionAllocator) -> Self {
+        let marker = allocator.marker();
+        let outer_reset_floor = mem::replace(&mut allocator.reset_floor, usize::MAX);
+
+        Self {
+            allocator,
+            marker,
+            outer_reset_floor,
+        }
+    }
+
+    /// Returns the marker the allocator will be reset to when the guard is dropped.
+    pub fn marker(&self) -> RegionMarker {
+        self.marker
+    }
+}
+
+impl Deref for ScopedRegion<'_> {
+    type Target = RegionAllocator;
+
+    fn deref(&self) -> &RegionAllocator {
+        self.allocator
+    }
+}
+
+impl DerefMut for ScopedRegion<'_> {
+    fn deref_mut(&mut self) -> &mut RegionAllocator {
+        self.allocator
+    }
+}
+
+impl Drop for ScopedRegion<'_> {
+    fn drop(&mut self) {
+        // The allocator could be cleared or reset below the marker inside the scope,
+        // the memory below the marker is already freed and may be in use again then.
+        if self.marker.0 <= self.allocator.reset_floor && self.marker <= self.allocator.marker() {
+            self.allocator.reset_to(self.marker);
+        }
+
+        self.allocator.reset_floor = self.allocator.reset_floor.min(self.outer_reset_floor);
+    }
+}
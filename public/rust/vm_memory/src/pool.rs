@@ -0,0 +1,263 @@
+use crate::c_api::*;
+use crate::{AllocError, BufferAccessor};
+use std::cell::Cell;
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// Pool allocator for blocks of a single size.
+///
+/// The memory is split into equal-size blocks, free blocks are linked into an intrusive
+/// free list, so both allocation and free take constant time. Blocks are freed in any order.
+/// The memory is released back to the system when the allocator is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let pool = PoolAllocator::new(24, 8, 2).unwrap();
+/// let first = pool.alloc().unwrap();
+/// let second = pool.alloc().unwrap();
+/// assert!(pool.alloc().is_err());
+///
+/// unsafe { pool.free(first) };
+/// assert_eq!(first, pool.alloc().unwrap());
+///
+/// unsafe { pool.free(second) };
+/// assert_eq!(1, pool.len());
+/// ```
+pub struct PoolAllocator {
+    pool: Cell<PoolMemoryBuffer>,
+}
+
+impl BufferAccessor for PoolAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.pool.get().base
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.pool.get().size
+    }
+}
+
+impl Drop for PoolAllocator {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        unsafe { pool_memory_buffer_destroy(self.pool.get_mut()) };
+    }
+}
+
+impl PoolAllocator {
+    /// Create a new allocator with `blocks_count` blocks of `block_size` bytes,
+    /// every block is aligned to `align` bytes.
+    ///
+    /// Blocks are at least the size of a pointer, the size of a block is rounded up
+    /// to a multiple of the alignment. A pool without blocks fails every allocation.
+    ///
+    /// # Errors
+    ///
+    /// If `align` is not a power of two or is larger than the page size, the size of
+    /// the pool can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let pool = PoolAllocator::new(16, 8, 0).unwrap();
+    /// assert_eq!(0, pool.capacity());
+    /// assert!(pool.alloc().is_err());
+    /// ```
+    pub fn new(block_size: usize, align: usize, blocks_count: usize) -> Result<Self, AllocError> {
+        if !align.is_power_of_two() || align as u64 > unsafe { virtual_page_size() } {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let size = block_size
+            .max(mem::size_of::<usize>())
+            .checked_next_multiple_of(align)
+            .and_then(|block_size| block_size.checked_mul(blocks_count));
+
+        if size.is_none_or(|size| size > isize::MAX as usize) {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let pool = unsafe {
+            create_pool_memory_buffer(block_size as u64, align as u64, blocks_count as u64)
+        };
+
+        if pool.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else {
+            Ok(Self {
+                pool: Cell::new(pool),
+            })
+        }
+    }
+
+    /// Allocate a block.
+    ///
+    /// # Errors
+    ///
+    /// If all the blocks are allocated, then this call will return an error.
+    pub fn alloc(&self) -> Result<*mut u8, AllocError> {
+        let data = unsafe { pool_memory_buffer_alloc(self.pool.as_ptr()) };
+
+        if data.is_null() {
+            Err(AllocError::OutOfMemory {
+                requested: self.block_size(),
+                available: 0,
+            })
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Return the block at `ptr` to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a block allocated by this pool that is not freed yet,
+    /// the block must not be used after this call.
+    pub unsafe fn free(&self, ptr: *mut u8) {
+        pool_memory_buffer_free_block(self.pool.as_ptr(), ptr);
+    }
+
+    /// Returns the size of a block, including the padding up to the alignment.
+    pub fn block_size(&self) -> usize {
+        self.pool.get().block_size as usize
+    }
+
+    /// Returns the number of blocks in the pool.
+    pub fn capacity(&self) -> usize {
+        self.pool.get().blocks_count as usize
+    }
+
+    /// Returns the number of allocated blocks.
+    pub fn len(&self) -> usize {
+        self.pool.get().used_blocks as usize
+    }
+
+    /// Returns `true` if there are no allocated blocks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Free all blocks.
+    pub fn clear(&mut self) {
+        unsafe { pool_memory_buffer_free(self.pool.get_mut()) };
+    }
+}
+
+/// Pool of values of a single type.
+///
+/// Every value gets its own block of a [`PoolAllocator`], the block is returned to
+/// the pool when the [`PoolBox`] that owns the value is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// struct Object {
+///     id: u32,
+///     fields: [u64; 4],
+/// }
+///
+/// let pool = Pool::new(2).unwrap();
+/// let mut first = pool.alloc(Object { id: 1, fields: [0; 4] }).unwrap();
+/// let second = pool.alloc(Object { id: 2, fields: [0; 4] }).unwrap();
+/// assert!(pool.alloc(Object { id: 3, fields: [0; 4] }).is_err());
+///
+/// first.fields[0] = 42;
+/// assert_eq!(42, first.fields[0]);
+///
+/// drop(first);
+/// let third = pool.alloc(Object { id: 3, fields: [0; 4] }).unwrap();
+///
+/// assert_eq!(2, second.id);
+/// assert_eq!(3, third.id);
+/// assert_eq!(2, pool.len());
+/// ```
+pub struct Pool<T> {
+    allocator: PoolAllocator,
+    values: PhantomData<T>,
+}
+
+impl<T> Pool<T> {
+    /// Create a new pool that fits `capacity` values.
+    ///
+    /// # Errors
+    ///
+    /// If the alignment of `T` is larger than the page size, the size of the pool can't
+    /// be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    pub fn new(capacity: usize) -> Result<Self, AllocError> {
+        Ok(Self {
+            allocator: PoolAllocator::new(mem::size_of::<T>(), mem::align_of::<T>(), capacity)?,
+            values: PhantomData,
+        })
+    }
+
+    /// Move the `value` to the pool.
+    ///
+    /// # Errors
+    ///
+    /// If the pool is full, then this call will return an error.
+    pub fn alloc(&self, value: T) -> Result<PoolBox<'_, T>, AllocError> {
+        let data = self.allocator.alloc()? as *mut T;
+        unsafe { data.write(value) };
+
+        Ok(PoolBox { pool: self, data })
+    }
+
+    /// Returns the number of values the pool fits.
+    pub fn capacity(&self) -> usize {
+        self.allocator.capacity()
+    }
+
+    /// Returns the number of values in the pool.
+    pub fn len(&self) -> usize {
+        self.allocator.len()
+    }
+
+    /// Returns `true` if there are no values in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.allocator.is_empty()
+    }
+}
+
+/// A value located in the memory of a [`Pool`].
+///
+/// The value is dropped and its block is returned to the pool when the box is dropped.
+pub struct PoolBox<'a, T> {
+    pool: &'a Pool<T>,
+    data: *mut T,
+}
+
+impl<T> Deref for PoolBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.data }
+    }
+}
+
+impl<T> DerefMut for PoolBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.data }
+    }
+}
+
+impl<T> Drop for PoolBox<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            self.data.drop_in_place();
+            self.pool.allocator.free(self.data as *mut u8);
+        }
+    }
+}
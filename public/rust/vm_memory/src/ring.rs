@@ -0,0 +1,154 @@
+use crate::{AllocError, BufferAccessor, RegionAllocator};
+
+/// A position in the stream of allocations of a [`RingAllocator`].
+///
+/// Positions grow monotonically, freeing the ring up to a marker frees every
+/// allocation made before the marker was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RingMarker(u64);
+
+/// Ring buffer allocator for streaming data between a producer and a consumer.
+///
+/// The producer allocates at the head of the ring, the consumer frees the oldest
+/// allocations at the tail of the ring with [`free_until`](Self::free_until).
+/// An allocation never wraps around the end of the ring, the remaining space
+/// at the end is skipped instead. Allocation fails when the ring is full.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut ring = RingAllocator::new(1024).unwrap();
+/// ring.alloc(400, 8).unwrap();
+/// let first = ring.marker();
+/// ring.alloc(400, 8).unwrap();
+/// let second = ring.marker();
+///
+/// assert!(ring.alloc(400, 8).is_err());
+///
+/// // The consumer is done with the first packet, the next one wraps around.
+/// ring.free_until(first);
+/// let packet = ring.alloc(400, 8).unwrap();
+///
+/// assert_eq!(ring.get_buffer_ptr(), packet);
+/// assert_eq!(1024, ring.used());
+///
+/// // The space skipped at the end of the ring is in use until the wrapped packet is freed.
+/// ring.free_until(second);
+/// assert_eq!(1024 - 800 + 400, ring.used());
+/// ```
+pub struct RingAllocator {
+    allocator: RegionAllocator,
+    head: u64,
+    tail: u64,
+}
+
+impl BufferAccessor for RingAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.allocator.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.allocator.get_buffer_size()
+    }
+}
+
+impl RingAllocator {
+    /// Create a new ring with a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        Ok(Self {
+            allocator: RegionAllocator::new(size)?,
+            head: 0,
+            tail: 0,
+        })
+    }
+
+    /// Allocate a new chunk of memory with a specific size at the head of the ring,
+    /// the returned address is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the ring is full or `align` is not a power of two,
+    /// then this call will return an error.
+    pub fn alloc(&mut self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let capacity = self.get_buffer_size() as usize;
+        let base = self.get_buffer_ptr() as usize;
+        // An empty ring has no position to wrap around.
+        let position = self.head.checked_rem(capacity as u64).unwrap_or(0) as usize;
+        let mut lap = self.head - position as u64;
+        let mut start = ((base + position + align - 1) & !(align - 1)) - base;
+
+        // Don't split the allocation, skip the rest of the ring instead.
+        if start
+            .checked_add(size)
+            .filter(|&end| end <= capacity)
+            .is_none()
+        {
+            lap += capacity as u64;
+            start = ((base + align - 1) & !(align - 1)) - base;
+        }
+
+        let end = start
+            .checked_add(size)
+            .filter(|&end| end <= capacity)
+            .map(|end| lap + end as u64)
+            .filter(|&end| end - self.tail <= capacity as u64)
+            .ok_or(AllocError::OutOfMemory {
+                requested: size,
+                available: self.available(),
+            })?;
+
+        self.head = end;
+        Ok(unsafe { self.get_buffer_ptr().add(start) })
+    }
+
+    /// Returns a marker of the head of the ring.
+    pub fn marker(&self) -> RingMarker {
+        RingMarker(self.head)
+    }
+
+    /// Free every allocation made before the `marker` was taken.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `marker` is already freed or is ahead of the head of the ring.
+    pub fn free_until(&mut self, marker: RingMarker) {
+        assert!(
+            self.tail <= marker.0 && marker.0 <= self.head,
+            "marker is outside of the used part of the ring"
+        );
+
+        self.tail = marker.0;
+    }
+
+    /// Returns the number of bytes in use, including alignment padding and the space
+    /// skipped at the end of the ring.
+    pub fn used(&self) -> usize {
+        (self.head - self.tail) as usize
+    }
+
+    /// Returns the number of bytes left in the ring.
+    pub fn available(&self) -> usize {
+        self.get_buffer_size() as usize - self.used()
+    }
+
+    /// Returns `true` if there are no allocations in the ring.
+    pub fn is_empty(&self) -> bool {
+        self.head == self.tail
+    }
+
+    /// Free all memory.
+    pub fn clear(&mut self) {
+        self.tail = self.head;
+    }
+}
@@ -0,0 +1,297 @@
+use crate::c_api::*;
+use crate::{AllocError, BufferAccessor, RegionAllocator};
+use std::ptr;
+
+/// Sizes of the blocks handed out by [`SlabAllocator`].
+pub const SLAB_SIZE_CLASSES: [usize; 9] = [16, 32, 64, 128, 256, 512, 1024, 2048, 4096];
+
+const NO_PAGE: u32 = u32::MAX;
+
+/// Bookkeeping of a single page of the backing region.
+#[derive(Clone, Copy)]
+struct SlabPage {
+    /// Free blocks of the page linked into an intrusive list.
+    free_list: *mut u8,
+    /// Number of allocated blocks.
+    used: u32,
+    /// Number of blocks that were handed out at least once.
+    initialized: u32,
+    /// Index of the size class, the page is empty if it's not in use by any class.
+    class: usize,
+    /// Links of the list of pages with free blocks of the same class,
+    /// or the list of empty pages.
+    previous: u32,
+    next: u32,
+}
+
+/// Slab allocator for small objects of different sizes.
+///
+/// Every allocation is routed to the smallest of [`SLAB_SIZE_CLASSES`] that fits it.
+/// Each size class takes pages from a backing region and splits them into blocks of
+/// the class size, free blocks are linked into an intrusive free list per page.
+/// When all blocks of a page are freed, the page goes back to the backing region,
+/// its memory is released to the system and the page can be reused by any size class.
+///
+/// Blocks are aligned to their class size.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let mut slab = SlabAllocator::new(1 << 20).unwrap();
+/// let small = slab.alloc(10).unwrap();
+/// let large = slab.alloc(3000).unwrap();
+///
+/// assert_eq!(0, small as usize % 16);
+/// assert_eq!(0, large as usize % 4096);
+/// assert_eq!(2, slab.used_pages());
+///
+/// unsafe { slab.free(large, 3000) };
+/// assert_eq!(1, slab.used_pages());
+///
+/// // The empty page is reused by another size class.
+/// slab.alloc(100).unwrap();
+/// assert_eq!(2, slab.used_pages());
+/// assert_eq!(2, slab.total_pages());
+/// ```
+pub struct SlabAllocator {
+    allocator: RegionAllocator,
+    page_size: usize,
+    pages: Vec<SlabPage>,
+    /// Heads of the lists of pages with free blocks, one list per size class.
+    partial: [u32; SLAB_SIZE_CLASSES.len()],
+    /// Head of the list of empty pages.
+    empty: u32,
+    used_pages: usize,
+}
+
+impl BufferAccessor for SlabAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.allocator.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.allocator.get_buffer_size()
+    }
+}
+
+impl SlabAllocator {
+    /// Create a new allocator with a backing region of a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        let page_size = unsafe { virtual_page_size() } as usize;
+
+        Ok(Self {
+            allocator: RegionAllocator::new(size)?,
+            page_size,
+            pages: Vec::new(),
+            partial: [NO_PAGE; SLAB_SIZE_CLASSES.len()],
+            empty: NO_PAGE,
+            used_pages: 0,
+        })
+    }
+
+    /// Allocate a new chunk of memory with a specific size from the smallest size class
+    /// that fits it.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` is larger than the largest size class, then this call will return
+    /// [`AllocError::InvalidSize`]. If the backing region is full, then this call will return
+    /// [`AllocError::OutOfMemory`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = SlabAllocator::new(1 << 20).unwrap();
+    /// let largest = SLAB_SIZE_CLASSES[SLAB_SIZE_CLASSES.len() - 1];
+    ///
+    /// assert!(allocator.alloc(largest).is_ok());
+    /// assert_eq!(Err(AllocError::InvalidSize), allocator.alloc(largest + 1));
+    /// ```
+    pub fn alloc(&mut self, size: usize) -> Result<*mut u8, AllocError> {
+        let class = size_class(size).ok_or(AllocError::InvalidSize)?;
+
+        let index = match self.partial[class] {
+            NO_PAGE => {
+                let index = self.take_page(class).ok_or(AllocError::OutOfMemory {
+                    requested: size,
+                    available: 0,
+                })?;
+
+                self.push(class, index);
+                index
+            }
+            index => index,
+        };
+
+        let block_size = SLAB_SIZE_CLASSES[class];
+        let base = self.page_ptr(index);
+        let page = &mut self.pages[index as usize];
+
+        let data = if !page.free_list.is_null() {
+            let data = page.free_list;
+            page.free_list = unsafe { (data as *mut *mut u8).read() };
+            data
+        } else {
+            // Blocks that were never used are not linked into the free list.
+            let data = unsafe { base.add(page.initialized as usize * block_size) };
+            page.initialized += 1;
+            data
+        };
+
+        page.used += 1;
+
+        if page.used as usize == self.page_size / block_size {
+            self.unlink(index);
+        }
+
+        Ok(data)
+    }
+
+    /// Return the chunk of memory at `ptr` to its size class.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by this allocator with the same `size` and not freed yet,
+    /// the memory must not be used after this call.
+    pub unsafe fn free(&mut self, ptr: *mut u8, size: usize) {
+        let class = size_class(size).expect("size is larger than the largest size class");
+        let index = ((ptr as usize - self.get_buffer_ptr() as usize) / self.page_size) as u32;
+        let blocks_count = self.page_size / SLAB_SIZE_CLASSES[class];
+        let page = &mut self.pages[index as usize];
+
+        debug_assert_eq!(class, page.class, "size doesn't match the allocation");
+
+        (ptr as *mut *mut u8).write(page.free_list);
+        page.free_list = ptr;
+        page.used -= 1;
+
+        if page.used == 0 {
+            if blocks_count > 1 {
+                self.unlink(index);
+            }
+
+            self.release_page(index);
+        } else if page.used as usize == blocks_count - 1 {
+            self.push(class, index);
+        }
+    }
+
+    /// Returns the size of the pages taken from the backing region.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Returns the number of pages in use by the size classes.
+    pub fn used_pages(&self) -> usize {
+        self.used_pages
+    }
+
+    /// Returns the number of pages taken from the backing region, including empty pages.
+    pub fn total_pages(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn page_ptr(&self, index: u32) -> *mut u8 {
+        unsafe { self.get_buffer_ptr().add(index as usize * self.page_size) }
+    }
+
+    /// Take an empty page for the `class`, reusing a released page if there is one.
+    fn take_page(&mut self, class: usize) -> Option<u32> {
+        let index = if self.empty != NO_PAGE {
+            let index = self.empty;
+            self.unlink(index);
+            index
+        } else {
+            self.allocator
+                .alloc_aligned(self.page_size, self.page_size)
+                .ok()?;
+
+            self.pages.push(SlabPage {
+                free_list: ptr::null_mut(),
+                used: 0,
+                initialized: 0,
+                class,
+                previous: NO_PAGE,
+                next: NO_PAGE,
+            });
+
+            (self.pages.len() - 1) as u32
+        };
+
+        let page = &mut self.pages[index as usize];
+        page.free_list = ptr::null_mut();
+        page.initialized = 0;
+        page.class = class;
+
+        self.used_pages += 1;
+        Some(index)
+    }
+
+    /// Give the empty page back to the backing region.
+    fn release_page(&mut self, index: u32) {
+        unsafe { virtual_release(self.page_ptr(index), self.page_size as u64) };
+
+        self.pages[index as usize].class = SLAB_SIZE_CLASSES.len();
+        self.push(SLAB_SIZE_CLASSES.len(), index);
+        self.used_pages -= 1;
+    }
+
+    fn head(&mut self, class: usize) -> &mut u32 {
+        if class == SLAB_SIZE_CLASSES.len() {
+            &mut self.empty
+        } else {
+            &mut self.partial[class]
+        }
+    }
+
+    /// Push the page to the front of the list of its class.
+    fn push(&mut self, class: usize, index: u32) {
+        let next = *self.head(class);
+
+        if next != NO_PAGE {
+            self.pages[next as usize].previous = index;
+        }
+
+        let page = &mut self.pages[index as usize];
+        page.previous = NO_PAGE;
+        page.next = next;
+
+        *self.head(class) = index;
+    }
+
+    /// Remove the page from the list of its class.
+    fn unlink(&mut self, index: u32) {
+        let SlabPage {
+            previous,
+            next,
+            class,
+            ..
+        } = self.pages[index as usize];
+
+        if previous == NO_PAGE {
+            *self.head(class) = next;
+        } else {
+            self.pages[previous as usize].next = next;
+        }
+
+        if next != NO_PAGE {
+            self.pages[next as usize].previous = previous;
+        }
+    }
+}
+
+/// Returns the index of the smallest size class that fits `size`.
+fn size_class(size: usize) -> Option<usize> {
+    SLAB_SIZE_CLASSES
+        .iter()
+        .position(|&class_size| size <= class_size)
+}
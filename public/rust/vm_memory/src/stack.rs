@@ -0,0 +1,172 @@
+use crate::c_api::*;
+use crate::{AllocError, BufferAccessor};
+use std::io;
+
+/// Stack based allocator.
+///
+/// Allocations are freed in the reverse order of allocation.
+/// Every allocation is preceded by a header that records the position of the stack
+/// before the allocation, so popping an allocation restores the stack exactly.
+/// Out of order frees through [`free`](Self::free) are detected in debug builds.
+/// The memory is released back to the system when the allocator is dropped.
+pub struct StackAllocator {
+    stack: StackMemoryBuffer,
+}
+
+impl BufferAccessor for StackAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.stack.base
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.stack.size
+    }
+}
+
+impl Drop for StackAllocator {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        unsafe { stack_memory_buffer_destroy(&mut self.stack as *mut StackMemoryBuffer) };
+    }
+}
+
+impl StackAllocator {
+    /// Create a new allocator with a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform or the system fails to map the memory,
+    /// then this call will return an error.
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        if size > isize::MAX as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let stack = unsafe { create_stack_memory_buffer(size as u64) };
+
+        if stack.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else {
+            Ok(Self { stack })
+        }
+    }
+
+    /// Push a new chunk of memory with a specific size on top of the stack, the returned
+    /// address is aligned to `align` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the memory is run out or `align` is not a power of two,
+    /// then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = StackAllocator::new(1024).unwrap();
+    /// let first = allocator.push(10, 1).unwrap();
+    /// let second = allocator.push(64, 64).unwrap();
+    ///
+    /// assert_eq!(0, second as usize % 64);
+    ///
+    /// allocator.free(second);
+    /// allocator.free(first);
+    /// assert!(allocator.is_empty());
+    /// ```
+    pub fn push(&mut self, size: usize, align: usize) -> Result<*mut u8, AllocError> {
+        if !align.is_power_of_two() {
+            return Err(AllocError::InvalidAlignment);
+        }
+
+        let data = unsafe {
+            stack_memory_buffer_push(
+                &mut self.stack as *mut StackMemoryBuffer,
+                size as u64,
+                align as u64,
+            )
+        };
+
+        if data.is_null() {
+            Err(AllocError::OutOfMemory {
+                requested: size,
+                available: self.stack.size as usize - self.stack.offset,
+            })
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Free the allocation on top of the stack, does nothing if the stack is empty.
+    pub fn pop(&mut self) {
+        unsafe { stack_memory_buffer_pop(&mut self.stack as *mut StackMemoryBuffer) };
+    }
+
+    /// Free the allocation at `ptr` and every allocation made after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ptr` is not an allocation on the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let mut allocator = StackAllocator::new(1024).unwrap();
+    /// let first = allocator.push(16, 8).unwrap();
+    /// let offset = allocator.offset();
+    ///
+    /// let second = allocator.push(16, 8).unwrap();
+    /// allocator.push(16, 8).unwrap();
+    /// allocator.pop_to(second);
+    ///
+    /// assert_eq!(offset, allocator.offset());
+    /// assert_eq!(first, allocator.top());
+    /// ```
+    // The pointer is never dereferenced, only compared against the allocations on the stack.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn pop_to(&mut self, ptr: *mut u8) {
+        let popped =
+            unsafe { stack_memory_buffer_pop_to(&mut self.stack as *mut StackMemoryBuffer, ptr) };
+
+        assert!(popped, "pointer is not an allocation on the stack");
+    }
+
+    /// Free the allocation at `ptr`, that should be the allocation on top of the stack.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds panics if `ptr` is not on top of the stack,
+    /// in release builds frees every allocation made after `ptr` as well.
+    /// Panics if `ptr` is not an allocation on the stack.
+    pub fn free(&mut self, ptr: *mut u8) {
+        debug_assert_eq!(self.top(), ptr, "out of order free on the stack");
+        self.pop_to(ptr);
+    }
+
+    /// Returns the allocation on top of the stack or null if the stack is empty.
+    pub fn top(&self) -> *mut u8 {
+        if self.stack.top == 0 {
+            std::ptr::null_mut()
+        } else {
+            unsafe { self.stack.base.add(self.stack.top) }
+        }
+    }
+
+    /// Returns the number of bytes used by the stack, including headers and padding.
+    pub fn offset(&self) -> usize {
+        self.stack.offset
+    }
+
+    /// Returns `true` if there are no allocations on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.stack.top == 0
+    }
+
+    /// Free all memory.
+    pub fn clear(&mut self) {
+        unsafe { stack_memory_buffer_free(&mut self.stack as *mut StackMemoryBuffer) };
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{BufferAccessor, RegionAllocator, RegionMarker};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Region based allocator carved out of the memory of a parent allocator.
+///
+/// Created by [`RegionAllocator::split_off`], the sub-region borrows the parent,
+/// so the parent can't be cleared or dropped while the sub-region is alive.
+/// The memory is owned by the parent and is not released when the sub-region is dropped.
+///
+/// Dereferences to a [`RegionAllocator`] that allocates from the sub-region.
+pub struct SubRegionAllocator<'a> {
+    allocator: RegionAllocator,
+    parent: PhantomData<&'a RegionAllocator>,
+}
+
+impl<'a> SubRegionAllocator<'a> {
+    pub(crate) fn new(allocator: RegionAllocator) -> Self {
+        Self {
+            allocator,
+            parent: PhantomData,
+        }
+    }
+
+    /// Free every allocation made after the `marker` was taken.
+    /// See [`RegionAllocator::reset_to`].
+    pub fn reset_to(&mut self, marker: RegionMarker) {
+        self.allocator.reset_to(marker)
+    }
+
+    /// Free all memory of the sub-region, the memory stays reserved in the parent.
+    pub fn clear(&mut self) {
+        self.allocator.clear()
+    }
+}
+
+impl Deref for SubRegionAllocator<'_> {
+    type Target = RegionAllocator;
+
+    fn deref(&self) -> &RegionAllocator {
+        &self.allocator
+    }
+}
+
+impl BufferAccessor for SubRegionAllocator<'_> {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.allocator.get_buffer_ptr()
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.allocator.get_buffer_size()
+    }
+}
@@ -0,0 +1,214 @@
+use crate::c_api::*;
+use crate::{AllocError, BufferAccessor};
+use std::cell::Cell;
+use std::io;
+
+/// Two-level segregated fit allocator for allocations of any size.
+///
+/// Free blocks are kept in segregated free lists indexed by two levels of size classes,
+/// with a bitmap of non-empty lists for each level, so finding a free block that fits
+/// an allocation, splitting it and merging a freed block with its free neighbours
+/// take bounded time regardless of the number of blocks.
+///
+/// The bookkeeping lives at the beginning of the region, the rest of the region is
+/// split into blocks. Every allocation is aligned to [`DEFAULT_ALIGNMENT`] bytes.
+/// The memory is released back to the system when the allocator is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use vm_memory::*;
+///
+/// let allocator = TlsfAllocator::new(1 << 20).unwrap();
+/// let first = allocator.alloc(100).unwrap();
+/// let second = allocator.alloc(5000).unwrap();
+///
+/// assert_eq!(0, first as usize % DEFAULT_ALIGNMENT);
+/// assert_eq!(112 + 5008, allocator.used_size());
+///
+/// unsafe {
+///     allocator.free(first);
+///     allocator.free(second);
+/// }
+///
+/// assert_eq!(0, allocator.used_size());
+/// ```
+///
+/// [`DEFAULT_ALIGNMENT`]: crate::DEFAULT_ALIGNMENT
+pub struct TlsfAllocator {
+    tlsf: Cell<TlsfMemoryBuffer>,
+}
+
+impl BufferAccessor for TlsfAllocator {
+    fn get_buffer_ptr(&self) -> *mut u8 {
+        self.tlsf.get().base
+    }
+
+    fn get_buffer_size(&self) -> u64 {
+        self.tlsf.get().size
+    }
+}
+
+impl Drop for TlsfAllocator {
+    /// Release the memory reserved for the allocator back to the system.
+    fn drop(&mut self) {
+        unsafe { tlsf_memory_buffer_destroy(self.tlsf.get_mut()) };
+    }
+}
+
+impl TlsfAllocator {
+    /// Create a new allocator with a specific size.
+    ///
+    /// # Errors
+    ///
+    /// If the `size` can't be mapped on this platform, the system fails to map the memory
+    /// or the `size` is too small to fit the bookkeeping of the allocator,
+    /// then this call will return an error.
+    pub fn new(size: usize) -> Result<Self, AllocError> {
+        if size > isize::MAX as usize {
+            return Err(AllocError::RegionTooLarge);
+        }
+
+        let mut tlsf = unsafe { create_tlsf_memory_buffer(size as u64) };
+
+        if tlsf.region.base.is_null() {
+            let errno = io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            Err(AllocError::MappingFailed(errno))
+        } else if tlsf.base.is_null() {
+            unsafe { tlsf_memory_buffer_destroy(&mut tlsf) };
+
+            Err(AllocError::OutOfMemory {
+                requested: size,
+                available: 0,
+            })
+        } else {
+            Ok(Self {
+                tlsf: Cell::new(tlsf),
+            })
+        }
+    }
+
+    /// Allocate a new chunk of memory with a specific size.
+    ///
+    /// The size is rounded up to the next size class to find a free block in bounded time,
+    /// so an allocation can fail even when a free block of the requested size exists,
+    /// see [`max_alloc_size`](Self::max_alloc_size).
+    ///
+    /// # Errors
+    ///
+    /// If there is no free block large enough, then this call will return an error,
+    /// that reports [`max_alloc_size`](Self::max_alloc_size) as the available size.
+    pub fn alloc(&self, size: usize) -> Result<*mut u8, AllocError> {
+        let data = unsafe { tlsf_memory_buffer_alloc(self.tlsf.as_ptr(), size as u64) };
+
+        if data.is_null() {
+            Err(self.out_of_memory(size))
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Resize the allocation at `ptr` to `new_size` bytes.
+    ///
+    /// The allocation grows in place if the block after it is free, otherwise the data
+    /// is moved to a new block. Shrinking always happens in place.
+    /// If `ptr` is null, then a new chunk of memory is allocated.
+    ///
+    /// # Errors
+    ///
+    /// If there is no free block large enough, then this call will return an error,
+    /// the allocation at `ptr` stays untouched.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or allocated by this allocator and not freed yet.
+    /// If the allocation is moved, the old pointer must not be used after this call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = TlsfAllocator::new(1 << 20).unwrap();
+    /// let data = allocator.alloc(4).unwrap();
+    /// unsafe { data.copy_from([1, 2, 3, 4].as_ptr(), 4) };
+    ///
+    /// let data = unsafe { allocator.realloc(data, 4096).unwrap() };
+    /// assert_eq!([1, 2, 3, 4], unsafe { *(data as *const [u8; 4]) });
+    /// assert_eq!(4096, allocator.used_size());
+    /// ```
+    pub unsafe fn realloc(&self, ptr: *mut u8, new_size: usize) -> Result<*mut u8, AllocError> {
+        let data = tlsf_memory_buffer_realloc(self.tlsf.as_ptr(), ptr, new_size as u64);
+
+        if data.is_null() {
+            Err(self.out_of_memory(new_size))
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Return the allocation at `ptr` to the allocator, merging it with the free
+    /// neighbouring blocks.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by this allocator and not freed yet,
+    /// the memory must not be used after this call.
+    pub unsafe fn free(&self, ptr: *mut u8) {
+        tlsf_memory_buffer_free_block(self.tlsf.as_ptr(), ptr);
+    }
+
+    /// Returns the number of usable bytes of the allocation at `ptr`,
+    /// at least the requested size.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by this allocator and not freed yet.
+    pub unsafe fn usable_size(&self, ptr: *const u8) -> usize {
+        tlsf_memory_buffer_get_block_size(self.tlsf.as_ptr(), ptr) as usize
+    }
+
+    /// Returns the number of bytes in allocated blocks, including the rounding up of the sizes.
+    pub fn used_size(&self) -> usize {
+        self.tlsf.get().used_size as usize
+    }
+
+    /// Returns the size of the largest allocation that is guaranteed to succeed.
+    ///
+    /// Requests are rounded up to the next size class, so it's up to 1/16 smaller
+    /// than the largest free block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let allocator = TlsfAllocator::new(1 << 22).unwrap();
+    /// let max_alloc_size = allocator.max_alloc_size();
+    /// assert!(max_alloc_size < allocator.get_buffer_size() as usize);
+    ///
+    /// let requested = max_alloc_size + 1;
+    /// assert_eq!(
+    ///     Err(AllocError::OutOfMemory { requested, available: max_alloc_size }),
+    ///     allocator.alloc(requested)
+    /// );
+    ///
+    /// allocator.alloc(max_alloc_size).unwrap();
+    /// assert!(allocator.max_alloc_size() < max_alloc_size);
+    /// ```
+    pub fn max_alloc_size(&self) -> usize {
+        unsafe { tlsf_memory_buffer_get_max_alloc_size(self.tlsf.as_ptr()) as usize }
+    }
+
+    /// Free all memory.
+    pub fn clear(&mut self) {
+        unsafe { tlsf_memory_buffer_free(self.tlsf.get_mut()) };
+    }
+
+    fn out_of_memory(&self, requested: usize) -> AllocError {
+        AllocError::OutOfMemory {
+            requested,
+            available: self.max_alloc_size(),
+        }
+    }
+}
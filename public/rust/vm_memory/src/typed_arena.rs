@@ -0,0 +1,174 @@
+use crate::{AllocError, RegionAllocator};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+/// Region based arena for values of a single type that runs the destructors of the values.
+///
+/// Values are moved into the arena and laid out one after another,
+/// so the arena only needs to track the number of values to drop them.
+/// The values are dropped in reverse order on [`clear`](Self::clear) and
+/// when the arena is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use vm_memory::*;
+///
+/// struct Noisy(u32, Rc<RefCell<Vec<u32>>>);
+///
+/// impl Drop for Noisy {
+///     fn drop(&mut self) {
+///         self.1.borrow_mut().push(self.0);
+///     }
+/// }
+///
+/// let dropped = Rc::new(RefCell::new(Vec::new()));
+/// let mut arena = TypedArena::new(16).unwrap();
+///
+/// for i in 0..3 {
+///     arena.alloc(Noisy(i, dropped.clone())).unwrap();
+/// }
+///
+/// arena.clear();
+/// assert_eq!(vec![2, 1, 0], *dropped.borrow());
+///
+/// arena.alloc(Noisy(3, dropped.clone())).unwrap();
+/// drop(arena);
+/// assert_eq!(vec![2, 1, 0, 3], *dropped.borrow());
+/// ```
+pub struct TypedArena<T> {
+    allocator: RegionAllocator,
+    /// The address of the first value, the base of the region can be padded
+    /// to the alignment of `T`.
+    first: Cell<*mut T>,
+    len: Cell<usize>,
+    values: PhantomData<T>,
+}
+
+impl<T> TypedArena<T> {
+    /// Create a new arena that fits `capacity` values.
+    ///
+    /// # Errors
+    ///
+    /// If the size of the arena can't be mapped on this platform or the system fails
+    /// to map the memory, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// Values aligned beyond the page size are supported:
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use vm_memory::*;
+    ///
+    /// #[repr(align(8192))]
+    /// struct Page<'a>(&'a Cell<usize>);
+    ///
+    /// impl Drop for Page<'_> {
+    ///     fn drop(&mut self) {
+    ///         self.0.set(self.0.get() + 1);
+    ///     }
+    /// }
+    ///
+    /// let dropped = Cell::new(0);
+    /// let arena = TypedArena::new(2).unwrap();
+    /// let first = arena.alloc(Page(&dropped)).unwrap() as *const Page;
+    /// let second = arena.alloc(Page(&dropped)).unwrap() as *const Page;
+    ///
+    /// assert_eq!(0, first as usize % 8192);
+    /// assert_eq!(0, second as usize % 8192);
+    /// assert!(arena.alloc(Page(&dropped)).is_err());
+    ///
+    /// drop(arena);
+    /// assert_eq!(3, dropped.get());
+    /// ```
+    pub fn new(capacity: usize) -> Result<Self, AllocError> {
+        // Reserve space for the padding in front of the first value as well.
+        let size = capacity
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|size| size.checked_add(mem::align_of::<T>() - 1))
+            .ok_or(AllocError::RegionTooLarge)?;
+
+        Ok(Self {
+            allocator: RegionAllocator::new(size)?,
+            first: Cell::new(ptr::null_mut()),
+            len: Cell::new(0),
+            values: PhantomData,
+        })
+    }
+
+    /// Move the `value` to the arena.
+    ///
+    /// Returns a reference to the value located in the memory of the arena.
+    ///
+    /// # Errors
+    ///
+    /// If the arena is full, then this call will return an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vm_memory::*;
+    ///
+    /// let arena = TypedArena::new(2).unwrap();
+    /// let first = arena.alloc(String::from("first")).unwrap();
+    /// let second = arena.alloc(String::from("second")).unwrap();
+    /// first.push('!');
+    ///
+    /// assert_eq!("first!", first);
+    /// assert_eq!("second", second);
+    /// assert!(arena.alloc(String::new()).is_err());
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc(&self, value: T) -> Result<&mut T, AllocError> {
+        let data = self.allocator.emplace(value)?;
+
+        if self.len.get() == 0 {
+            self.first.set(data as *mut T);
+        }
+
+        debug_assert_eq!(
+            self.first.get().wrapping_add(self.len.get()),
+            data as *mut T
+        );
+        self.len.set(self.len.get() + 1);
+        Ok(data)
+    }
+
+    /// Returns the number of values in the arena.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if the arena contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len.get() == 0
+    }
+
+    /// Drop all values in reverse order and free all memory.
+    pub fn clear(&mut self) {
+        self.drop_values();
+        self.allocator.clear();
+    }
+
+    fn drop_values(&mut self) {
+        let base = self.first.get();
+
+        while self.len.get() > 0 {
+            // Shrink first, so a panicking destructor doesn't lead to a double drop.
+            let last = self.len.get() - 1;
+            self.len.set(last);
+            unsafe { ptr::drop_in_place(base.add(last)) };
+        }
+    }
+}
+
+impl<T> Drop for TypedArena<T> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
@@ -0,0 +1,221 @@
+#include "vm_memory.hpp"
+#include <assert.h>
+
+// Marks the order of a block that is in a free list.
+#define BUDDY_BLOCK_FREE 0x80
+
+// Stored at the beginning of every free block.
+struct BuddyFreeBlock {
+    BuddyFreeBlock* next;
+    BuddyFreeBlock* previous;
+};
+
+static void buddy_memory_buffer_reset(BuddyMemoryBuffer* buffer) {
+    memset(&buffer->region, 0, sizeof(RegionMemoryBuffer));
+    buffer->size = 0;
+    buffer->base = 0;
+    buffer->min_block_size = 0;
+    buffer->max_order = 0;
+    buffer->used_size = 0;
+    buffer->block_orders = 0;
+
+    for (uint32_t order = 0; order < BUDDY_MAX_ORDERS; ++order) {
+        buffer->free_lists[order] = 0;
+    }
+}
+
+static uint64_t buddy_memory_buffer_get_index(BuddyMemoryBuffer const* buffer, uint8_t const* block) {
+    return (block - buffer->base) / buffer->min_block_size;
+}
+
+static void buddy_memory_buffer_push(BuddyMemoryBuffer* buffer, uint32_t order, uint8_t* block) {
+    BuddyFreeBlock* free_block = (BuddyFreeBlock*) block;
+    BuddyFreeBlock* next = (BuddyFreeBlock*) buffer->free_lists[order];
+
+    free_block->next = next;
+    free_block->previous = 0;
+
+    if (next) {
+        next->previous = free_block;
+    }
+
+    buffer->free_lists[order] = block;
+    buffer->block_orders[buddy_memory_buffer_get_index(buffer, block)] = order | BUDDY_BLOCK_FREE;
+}
+
+static void buddy_memory_buffer_remove(BuddyMemoryBuffer* buffer, uint32_t order, uint8_t* block) {
+    BuddyFreeBlock* free_block = (BuddyFreeBlock*) block;
+
+    if (free_block->previous) {
+        free_block->previous->next = free_block->next;
+    }
+    else {
+        buffer->free_lists[order] = (uint8_t*) free_block->next;
+    }
+
+    if (free_block->next) {
+        free_block->next->previous = free_block->previous;
+    }
+}
+
+extern "C" BuddyMemoryBuffer create_buddy_memory_buffer(uint64_t size, uint64_t min_block_size) {
+    BuddyMemoryBuffer buffer;
+    buddy_memory_buffer_reset(&buffer);
+
+    if (min_block_size == 0 || (min_block_size & (min_block_size - 1)) != 0) {
+        return buffer;
+    }
+
+    // Every free block stores the links of the free list.
+    if (min_block_size < sizeof(BuddyFreeBlock)) {
+        min_block_size = sizeof(BuddyFreeBlock);
+    }
+
+    // Only the largest power of two number of blocks that fits the size is managed,
+    // but at least one block.
+    uint32_t max_order = 0;
+
+    while (max_order + 1 < BUDDY_MAX_ORDERS && (size / min_block_size) >> (max_order + 1) != 0) {
+        max_order += 1;
+    }
+
+    uint64_t blocks_count = (uint64_t) 1 << max_order;
+    uint64_t managed_size = min_block_size << max_order;
+
+    // The order of every block is stored right after the managed memory.
+    buffer.region = create_region_memory_buffer(managed_size + blocks_count);
+
+    if (!buffer.region.base) {
+        return buffer;
+    }
+
+    buffer.size = managed_size;
+    buffer.base = region_memory_buffer_alloc(&buffer.region, managed_size);
+    buffer.min_block_size = min_block_size;
+    buffer.max_order = max_order;
+    buffer.block_orders = region_memory_buffer_alloc(&buffer.region, blocks_count);
+
+    buddy_memory_buffer_free(&buffer);
+    return buffer;
+}
+
+extern "C" void buddy_memory_buffer_destroy(BuddyMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    region_memory_buffer_destroy(&buffer->region);
+    buddy_memory_buffer_reset(buffer);
+}
+
+extern "C" uint8_t* buddy_memory_buffer_alloc(BuddyMemoryBuffer* buffer, uint64_t size) {
+    assert(buffer != 0);
+
+    if (size > buffer->size) {
+        return 0;
+    }
+
+    uint32_t order = 0;
+
+    while ((buffer->min_block_size << order) < size) {
+        order += 1;
+    }
+
+    uint32_t free_order = order;
+
+    while (free_order <= buffer->max_order && !buffer->free_lists[free_order]) {
+        free_order += 1;
+    }
+
+    if (free_order > buffer->max_order) {
+        return 0;
+    }
+
+    uint8_t* block = buffer->free_lists[free_order];
+    buddy_memory_buffer_remove(buffer, free_order, block);
+
+    // Split the block in halves until it fits the size, the upper halves become free blocks.
+    while (free_order > order) {
+        free_order -= 1;
+        buddy_memory_buffer_push(buffer, free_order, block + (buffer->min_block_size << free_order));
+    }
+
+    buffer->block_orders[buddy_memory_buffer_get_index(buffer, block)] = order;
+    buffer->used_size += buffer->min_block_size << order;
+
+    return block;
+}
+
+extern "C" uint64_t buddy_memory_buffer_get_block_size(BuddyMemoryBuffer const* buffer, uint8_t const* ptr) {
+    assert(buffer != 0);
+    assert(ptr >= buffer->base && ptr < buffer->base + buffer->size);
+
+    uint8_t order = buffer->block_orders[buddy_memory_buffer_get_index(buffer, ptr)];
+    assert((order & BUDDY_BLOCK_FREE) == 0);
+
+    return buffer->min_block_size << order;
+}
+
+extern "C" void buddy_memory_buffer_free_block(BuddyMemoryBuffer* buffer, uint8_t* ptr) {
+    assert(buffer != 0);
+    assert(ptr >= buffer->base && ptr < buffer->base + buffer->size);
+    assert((ptr - buffer->base) % buffer->min_block_size == 0);
+
+    uint64_t index = buddy_memory_buffer_get_index(buffer, ptr);
+    uint32_t order = buffer->block_orders[index];
+    assert((order & BUDDY_BLOCK_FREE) == 0);
+
+    buffer->used_size -= buffer->min_block_size << order;
+
+    // Merge the block with its buddy while the buddy is a free block of the same order.
+    while (order < buffer->max_order) {
+        uint64_t buddy_index = index ^ ((uint64_t) 1 << order);
+
+        if (buffer->block_orders[buddy_index] != (order | BUDDY_BLOCK_FREE)) {
+            break;
+        }
+
+        buddy_memory_buffer_remove(buffer, order, buffer->base + buddy_index * buffer->min_block_size);
+        index &= ~((uint64_t) 1 << order);
+        order += 1;
+    }
+
+    buddy_memory_buffer_push(buffer, order, buffer->base + index * buffer->min_block_size);
+}
+
+extern "C" void buddy_memory_buffer_free(BuddyMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    for (uint32_t order = 0; order < BUDDY_MAX_ORDERS; ++order) {
+        buffer->free_lists[order] = 0;
+    }
+
+    buffer->used_size = 0;
+
+    if (buffer->base) {
+        buddy_memory_buffer_push(buffer, buffer->max_order, buffer->base);
+    }
+}
+
+extern "C" BuddyMemoryStats buddy_memory_buffer_get_stats(BuddyMemoryBuffer const* buffer) {
+    assert(buffer != 0);
+    BuddyMemoryStats stats;
+
+    stats.total_size = buffer->size;
+    stats.used_size = buffer->used_size;
+    stats.free_size = buffer->size - buffer->used_size;
+    stats.largest_free_block = 0;
+    stats.free_blocks_count = 0;
+
+    for (uint32_t order = 0; order <= buffer->max_order; ++order) {
+        BuddyFreeBlock* block = (BuddyFreeBlock*) buffer->free_lists[order];
+
+        if (block) {
+            stats.largest_free_block = buffer->min_block_size << order;
+        }
+
+        for (; block; block = block->next) {
+            stats.free_blocks_count += 1;
+        }
+    }
+
+    return stats;
+}
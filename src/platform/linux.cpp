@@ -1,15 +1,64 @@
 #include "vm_memory.hpp"
 #include <sys/mman.h>
 #include <fcntl.h>
+#include <unistd.h>
 
-extern "C" uint8_t* virtual_alloc(uint32_t size) {
+// Zero sized mappings fail, so every empty mapping gets this address instead,
+// that way every kind of buffer supports the zero size.
+alignas(4096) static uint8_t empty_mapping[1];
+
+extern "C" uint8_t* virtual_alloc(uint64_t size) {
+    if (size == 0) {
+        return empty_mapping;
+    }
+
+    // Anonymous mappings are already zero filled.
     void* base = mmap(0, size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
 
     if (base != MAP_FAILED) {
-        memset(base, 0, size);
         return (uint8_t*) base;
     }
     else {
         return 0;
     }
 }
+
+extern "C" void virtual_free(uint8_t* base, uint64_t size) {
+    if (base == empty_mapping) {
+        return;
+    }
+
+    munmap(base, size);
+}
+
+extern "C" uint8_t* virtual_reserve(uint64_t size) {
+    if (size == 0) {
+        return empty_mapping;
+    }
+
+    void* base = mmap(0, size, PROT_NONE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_NORESERVE, -1, 0);
+
+    if (base != MAP_FAILED) {
+        return (uint8_t*) base;
+    }
+    else {
+        return 0;
+    }
+}
+
+extern "C" bool virtual_commit(uint8_t* base, uint64_t size) {
+    return mprotect(base, size, PROT_READ | PROT_WRITE) == 0;
+}
+
+extern "C" void virtual_decommit(uint8_t* base, uint64_t size) {
+    madvise(base, size, MADV_DONTNEED);
+    mprotect(base, size, PROT_NONE);
+}
+
+extern "C" void virtual_release(uint8_t* base, uint64_t size) {
+    madvise(base, size, MADV_DONTNEED);
+}
+
+extern "C" uint64_t virtual_page_size() {
+    return sysconf(_SC_PAGESIZE);
+}
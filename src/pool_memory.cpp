@@ -0,0 +1,99 @@
+#include "vm_memory.hpp"
+#include <assert.h>
+
+static void pool_memory_buffer_reset(PoolMemoryBuffer* buffer) {
+    buffer->size = 0;
+    buffer->base = 0;
+    buffer->block_size = 0;
+    buffer->blocks_count = 0;
+    buffer->used_blocks = 0;
+    buffer->initialized_blocks = 0;
+    buffer->free_list = 0;
+}
+
+extern "C" PoolMemoryBuffer create_pool_memory_buffer(uint64_t block_size, uint64_t align, uint64_t blocks_count) {
+    PoolMemoryBuffer buffer;
+    pool_memory_buffer_reset(&buffer);
+
+    // The blocks are page aligned at most, since the pool starts at a page boundary.
+    if (align == 0 || (align & (align - 1)) != 0 || align > virtual_page_size()) {
+        return buffer;
+    }
+
+    // Every free block stores the pointer to the next free block.
+    if (block_size < sizeof(uint8_t*)) {
+        block_size = sizeof(uint8_t*);
+    }
+
+    if (align < alignof(uint8_t*)) {
+        align = alignof(uint8_t*);
+    }
+
+    block_size = (block_size + align - 1) & ~(align - 1);
+
+    if (blocks_count > UINT64_MAX / block_size) {
+        return buffer;
+    }
+
+    uint64_t size = block_size * blocks_count;
+    uint8_t* base = virtual_alloc(size);
+
+    if (base) {
+        buffer.size = size;
+        buffer.base = base;
+        buffer.block_size = block_size;
+        buffer.blocks_count = blocks_count;
+    }
+
+    return buffer;
+}
+
+extern "C" void pool_memory_buffer_destroy(PoolMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    if (buffer->base) {
+        virtual_free(buffer->base, buffer->size);
+    }
+
+    pool_memory_buffer_reset(buffer);
+}
+
+extern "C" uint8_t* pool_memory_buffer_alloc(PoolMemoryBuffer* buffer) {
+    assert(buffer != 0);
+    uint8_t* result;
+
+    if (buffer->free_list) {
+        result = buffer->free_list;
+        buffer->free_list = *(uint8_t**) result;
+    }
+    // Blocks that were never used are not linked into the free list,
+    // so creating a pool doesn't touch all of its pages.
+    else if (buffer->initialized_blocks < buffer->blocks_count) {
+        result = buffer->base + buffer->initialized_blocks * buffer->block_size;
+        buffer->initialized_blocks += 1;
+    }
+    else {
+        return 0;
+    }
+
+    buffer->used_blocks += 1;
+    return result;
+}
+
+extern "C" void pool_memory_buffer_free_block(PoolMemoryBuffer* buffer, uint8_t* ptr) {
+    assert(buffer != 0);
+    assert(ptr >= buffer->base && ptr < buffer->base + buffer->initialized_blocks * buffer->block_size);
+    assert((ptr - buffer->base) % buffer->block_size == 0);
+
+    *(uint8_t**) ptr = buffer->free_list;
+    buffer->free_list = ptr;
+    buffer->used_blocks -= 1;
+}
+
+extern "C" void pool_memory_buffer_free(PoolMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    buffer->used_blocks = 0;
+    buffer->initialized_blocks = 0;
+    buffer->free_list = 0;
+}
@@ -0,0 +1,117 @@
+#include "vm_memory.hpp"
+#include <assert.h>
+
+// Stored right before every allocation of the stack.
+struct StackMemoryHeader {
+    uintptr_t previous_offset;
+    uintptr_t previous_top;
+    uint64_t padding;
+};
+
+static StackMemoryHeader* stack_memory_buffer_get_header(StackMemoryBuffer* buffer, uintptr_t top) {
+    return (StackMemoryHeader*) (buffer->base + top - sizeof(StackMemoryHeader));
+}
+
+extern "C" StackMemoryBuffer create_stack_memory_buffer(uint64_t size) {
+    uint8_t* base = virtual_alloc(size);
+    StackMemoryBuffer buffer;
+
+    if (base) {
+        buffer.size = size;
+        buffer.base = base;
+    }
+    else {
+        buffer.size = 0;
+        buffer.base = 0;
+    }
+
+    buffer.offset = 0;
+    buffer.top = 0;
+
+    return buffer;
+}
+
+extern "C" void stack_memory_buffer_destroy(StackMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    if (buffer->base) {
+        virtual_free(buffer->base, buffer->size);
+    }
+
+    buffer->size = 0;
+    buffer->base = 0;
+    buffer->offset = 0;
+    buffer->top = 0;
+}
+
+extern "C" uint8_t* stack_memory_buffer_push(StackMemoryBuffer* buffer, uint64_t size, uint64_t align) {
+    assert(buffer != 0);
+
+    if (align == 0 || (align & (align - 1)) != 0) {
+        return 0;
+    }
+
+    if (align < alignof(StackMemoryHeader)) {
+        align = alignof(StackMemoryHeader);
+    }
+
+    uintptr_t address = (uintptr_t) (buffer->base + buffer->offset + sizeof(StackMemoryHeader));
+    uint64_t padding = (align - (address & (align - 1))) & (align - 1);
+    uint64_t header_size = sizeof(StackMemoryHeader) + padding;
+    uint64_t available = buffer->size - buffer->offset;
+
+    if (header_size > available || size > available - header_size) {
+        return 0;
+    }
+
+    uintptr_t top = buffer->offset + header_size;
+    StackMemoryHeader* header = stack_memory_buffer_get_header(buffer, top);
+
+    header->previous_offset = buffer->offset;
+    header->previous_top = buffer->top;
+    header->padding = padding;
+
+    buffer->offset = top + size;
+    buffer->top = top;
+
+    return buffer->base + top;
+}
+
+extern "C" void stack_memory_buffer_pop(StackMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    if (buffer->top != 0) {
+        StackMemoryHeader* header = stack_memory_buffer_get_header(buffer, buffer->top);
+
+        buffer->offset = header->previous_offset;
+        buffer->top = header->previous_top;
+    }
+}
+
+extern "C" bool stack_memory_buffer_pop_to(StackMemoryBuffer* buffer, uint8_t* ptr) {
+    assert(buffer != 0);
+    uintptr_t top = buffer->top;
+
+    // Walk down the stack, so a pointer that is not an allocation never corrupts the stack.
+    while (top != 0 && buffer->base + top > ptr) {
+        top = stack_memory_buffer_get_header(buffer, top)->previous_top;
+    }
+
+    if (top == 0 || buffer->base + top != ptr) {
+        return false;
+    }
+
+    StackMemoryHeader* header = stack_memory_buffer_get_header(buffer, top);
+
+    buffer->offset = header->previous_offset;
+    buffer->top = header->previous_top;
+
+    return true;
+}
+
+extern "C" void stack_memory_buffer_free(StackMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    buffer->offset = 0;
+    buffer->top = 0;
+}
@@ -0,0 +1,400 @@
+#include "vm_memory.hpp"
+#include <assert.h>
+
+// Block sizes and payloads are aligned to 16 bytes.
+#define TLSF_ALIGN_LOG2 4
+#define TLSF_ALIGN (1 << TLSF_ALIGN_LOG2)
+
+// Every first level class is split into 16 second level classes.
+#define TLSF_SL_LOG2 4
+#define TLSF_SL_COUNT (1 << TLSF_SL_LOG2)
+
+// Blocks smaller than 256 bytes are kept in the first first level class.
+#define TLSF_FL_SHIFT (TLSF_SL_LOG2 + TLSF_ALIGN_LOG2)
+#define TLSF_SMALL_BLOCK_SIZE ((uint64_t) 1 << TLSF_FL_SHIFT)
+#define TLSF_FL_COUNT 32
+
+#define TLSF_MAX_BLOCK_SIZE (((uint64_t) 1 << (TLSF_FL_COUNT + TLSF_FL_SHIFT - 1)) - TLSF_ALIGN)
+
+#define TLSF_BLOCK_FREE 1
+
+// Stored right before the payload of every block, the free list links
+// are only valid for free blocks and overlap the payload.
+struct TlsfBlock {
+    TlsfBlock* previous_physical;
+    // The size of the payload, the lowest bit marks a free block.
+    uint64_t size;
+    TlsfBlock* next_free;
+    TlsfBlock* previous_free;
+};
+
+#define TLSF_BLOCK_HEADER_SIZE (sizeof(TlsfBlock*) + sizeof(uint64_t))
+#define TLSF_MIN_BLOCK_SIZE (sizeof(TlsfBlock) - TLSF_BLOCK_HEADER_SIZE)
+
+// Segregated free lists, every bit of the bitmaps marks a non-empty list.
+struct TlsfControl {
+    uint32_t fl_bitmap;
+    uint32_t sl_bitmaps[TLSF_FL_COUNT];
+    TlsfBlock* free_lists[TLSF_FL_COUNT][TLSF_SL_COUNT];
+};
+
+static uint64_t tlsf_block_get_size(TlsfBlock const* block) {
+    return block->size & ~(uint64_t) TLSF_BLOCK_FREE;
+}
+
+static bool tlsf_block_is_free(TlsfBlock const* block) {
+    return (block->size & TLSF_BLOCK_FREE) != 0;
+}
+
+static uint8_t* tlsf_block_get_payload(TlsfBlock* block) {
+    return (uint8_t*) block + TLSF_BLOCK_HEADER_SIZE;
+}
+
+static TlsfBlock* tlsf_block_from_payload(uint8_t const* ptr) {
+    return (TlsfBlock*) (ptr - TLSF_BLOCK_HEADER_SIZE);
+}
+
+static TlsfBlock* tlsf_block_get_next(TlsfBlock* block) {
+    return (TlsfBlock*) (tlsf_block_get_payload(block) + tlsf_block_get_size(block));
+}
+
+static uint32_t tlsf_find_last_set(uint64_t value) {
+    return 63 - __builtin_clzll(value);
+}
+
+static uint32_t tlsf_find_first_set(uint32_t value) {
+    return __builtin_ctz(value);
+}
+
+static void tlsf_mapping_insert(uint64_t size, uint32_t* fl, uint32_t* sl) {
+    if (size < TLSF_SMALL_BLOCK_SIZE) {
+        *fl = 0;
+        *sl = (uint32_t) (size >> TLSF_ALIGN_LOG2);
+    }
+    else {
+        uint32_t last_set = tlsf_find_last_set(size);
+        *fl = last_set - TLSF_FL_SHIFT + 1;
+        *sl = (uint32_t) (size >> (last_set - TLSF_SL_LOG2)) ^ TLSF_SL_COUNT;
+    }
+}
+
+// Round the size up to the next class, so any block of the class fits the size.
+static void tlsf_mapping_search(uint64_t size, uint32_t* fl, uint32_t* sl) {
+    if (size >= TLSF_SMALL_BLOCK_SIZE) {
+        size += ((uint64_t) 1 << (tlsf_find_last_set(size) - TLSF_SL_LOG2)) - 1;
+    }
+
+    tlsf_mapping_insert(size, fl, sl);
+}
+
+static void tlsf_insert_block(TlsfMemoryBuffer* buffer, TlsfBlock* block) {
+    TlsfControl* control = (TlsfControl*) buffer->control;
+    uint32_t fl, sl;
+    tlsf_mapping_insert(tlsf_block_get_size(block), &fl, &sl);
+
+    TlsfBlock* next = control->free_lists[fl][sl];
+    block->next_free = next;
+    block->previous_free = 0;
+
+    if (next) {
+        next->previous_free = block;
+    }
+
+    control->free_lists[fl][sl] = block;
+    control->fl_bitmap |= 1u << fl;
+    control->sl_bitmaps[fl] |= 1u << sl;
+    block->size |= TLSF_BLOCK_FREE;
+}
+
+static void tlsf_remove_block(TlsfMemoryBuffer* buffer, TlsfBlock* block) {
+    TlsfControl* control = (TlsfControl*) buffer->control;
+    uint32_t fl, sl;
+    tlsf_mapping_insert(tlsf_block_get_size(block), &fl, &sl);
+
+    if (block->previous_free) {
+        block->previous_free->next_free = block->next_free;
+    }
+    else {
+        control->free_lists[fl][sl] = block->next_free;
+
+        if (!block->next_free) {
+            control->sl_bitmaps[fl] &= ~(1u << sl);
+
+            if (!control->sl_bitmaps[fl]) {
+                control->fl_bitmap &= ~(1u << fl);
+            }
+        }
+    }
+
+    if (block->next_free) {
+        block->next_free->previous_free = block->previous_free;
+    }
+
+    block->size &= ~(uint64_t) TLSF_BLOCK_FREE;
+}
+
+// The smallest size of the blocks in the class.
+static uint64_t tlsf_mapping_class_size(uint32_t fl, uint32_t sl) {
+    if (fl == 0) {
+        return (uint64_t) sl << TLSF_ALIGN_LOG2;
+    }
+
+    return (uint64_t) (TLSF_SL_COUNT | sl) << (fl + TLSF_FL_SHIFT - 1 - TLSF_SL_LOG2);
+}
+
+static TlsfBlock* tlsf_find_free_block(TlsfMemoryBuffer* buffer, uint64_t size) {
+    TlsfControl* control = (TlsfControl*) buffer->control;
+    uint32_t fl, sl;
+    tlsf_mapping_search(size, &fl, &sl);
+
+    if (fl >= TLSF_FL_COUNT) {
+        return 0;
+    }
+
+    uint32_t sl_bitmap = control->sl_bitmaps[fl] & (~0u << sl);
+
+    if (!sl_bitmap) {
+        uint32_t fl_bitmap = fl + 1 < TLSF_FL_COUNT ? control->fl_bitmap & (~0u << (fl + 1)) : 0;
+
+        if (!fl_bitmap) {
+            return 0;
+        }
+
+        fl = tlsf_find_first_set(fl_bitmap);
+        sl_bitmap = control->sl_bitmaps[fl];
+    }
+
+    return control->free_lists[fl][tlsf_find_first_set(sl_bitmap)];
+}
+
+// Cut the block down to the size, the rest becomes a free block if it is large enough.
+static void tlsf_split_block(TlsfMemoryBuffer* buffer, TlsfBlock* block, uint64_t size) {
+    uint64_t block_size = tlsf_block_get_size(block);
+
+    if (block_size < size + TLSF_BLOCK_HEADER_SIZE + TLSF_MIN_BLOCK_SIZE) {
+        return;
+    }
+
+    block->size = size;
+
+    TlsfBlock* rest = tlsf_block_get_next(block);
+    rest->previous_physical = block;
+    rest->size = block_size - size - TLSF_BLOCK_HEADER_SIZE;
+
+    TlsfBlock* next = tlsf_block_get_next(rest);
+
+    // The rest is merged with the next block, so free blocks are never adjacent.
+    if (tlsf_block_is_free(next)) {
+        tlsf_remove_block(buffer, next);
+        rest->size += TLSF_BLOCK_HEADER_SIZE + tlsf_block_get_size(next);
+        next = tlsf_block_get_next(rest);
+    }
+
+    next->previous_physical = rest;
+    tlsf_insert_block(buffer, rest);
+}
+
+static uint64_t tlsf_adjust_size(uint64_t size) {
+    size = (size + TLSF_ALIGN - 1) & ~(uint64_t) (TLSF_ALIGN - 1);
+    return size < TLSF_MIN_BLOCK_SIZE ? TLSF_MIN_BLOCK_SIZE : size;
+}
+
+extern "C" TlsfMemoryBuffer create_tlsf_memory_buffer(uint64_t size) {
+    TlsfMemoryBuffer buffer;
+
+    buffer.region = create_region_memory_buffer(size);
+    buffer.size = 0;
+    buffer.base = 0;
+    buffer.used_size = 0;
+    buffer.control = 0;
+
+    if (!buffer.region.base) {
+        return buffer;
+    }
+
+    // The control structure lives at the beginning of the region, the rest is the pool.
+    // The size is rounded up, so the pool that follows starts aligned.
+    uint64_t control_size = (sizeof(TlsfControl) + TLSF_ALIGN - 1) & ~(uint64_t) (TLSF_ALIGN - 1);
+    buffer.control = region_memory_buffer_alloc_aligned(&buffer.region, control_size, TLSF_ALIGN);
+    uint64_t pool_size = 0;
+
+    if (buffer.control) {
+        pool_size = (buffer.region.size - buffer.region.offset) & ~(uint64_t) (TLSF_ALIGN - 1);
+    }
+
+    // The pool needs the first block and the sentinel block that ends the pool,
+    // the region is left mapped without a pool if it is too small.
+    if (pool_size < 2 * TLSF_BLOCK_HEADER_SIZE + TLSF_MIN_BLOCK_SIZE) {
+        buffer.control = 0;
+        return buffer;
+    }
+
+    if (pool_size > TLSF_MAX_BLOCK_SIZE + 2 * TLSF_BLOCK_HEADER_SIZE) {
+        pool_size = TLSF_MAX_BLOCK_SIZE + 2 * TLSF_BLOCK_HEADER_SIZE;
+    }
+
+    buffer.size = pool_size;
+    buffer.base = region_memory_buffer_alloc_aligned(&buffer.region, pool_size, TLSF_ALIGN);
+
+    tlsf_memory_buffer_free(&buffer);
+    return buffer;
+}
+
+extern "C" void tlsf_memory_buffer_destroy(TlsfMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    region_memory_buffer_destroy(&buffer->region);
+
+    buffer->size = 0;
+    buffer->base = 0;
+    buffer->used_size = 0;
+    buffer->control = 0;
+}
+
+extern "C" uint8_t* tlsf_memory_buffer_alloc(TlsfMemoryBuffer* buffer, uint64_t size) {
+    assert(buffer != 0);
+
+    if (size > TLSF_MAX_BLOCK_SIZE) {
+        return 0;
+    }
+
+    size = tlsf_adjust_size(size);
+    TlsfBlock* block = tlsf_find_free_block(buffer, size);
+
+    if (!block) {
+        return 0;
+    }
+
+    tlsf_remove_block(buffer, block);
+    tlsf_split_block(buffer, block, size);
+    buffer->used_size += tlsf_block_get_size(block);
+
+    return tlsf_block_get_payload(block);
+}
+
+extern "C" uint8_t* tlsf_memory_buffer_realloc(TlsfMemoryBuffer* buffer, uint8_t* ptr, uint64_t new_size) {
+    assert(buffer != 0);
+
+    if (!ptr) {
+        return tlsf_memory_buffer_alloc(buffer, new_size);
+    }
+
+    if (new_size > TLSF_MAX_BLOCK_SIZE) {
+        return 0;
+    }
+
+    TlsfBlock* block = tlsf_block_from_payload(ptr);
+    TlsfBlock* next = tlsf_block_get_next(block);
+    uint64_t block_size = tlsf_block_get_size(block);
+    uint64_t size = tlsf_adjust_size(new_size);
+
+    assert(!tlsf_block_is_free(block));
+
+    // Grow in place by taking the next block if it is free and large enough.
+    if (size > block_size && tlsf_block_is_free(next) &&
+        block_size + TLSF_BLOCK_HEADER_SIZE + tlsf_block_get_size(next) >= size) {
+        tlsf_remove_block(buffer, next);
+        block->size = block_size + TLSF_BLOCK_HEADER_SIZE + tlsf_block_get_size(next);
+        tlsf_block_get_next(block)->previous_physical = block;
+    }
+
+    if (size <= tlsf_block_get_size(block)) {
+        buffer->used_size -= block_size;
+        tlsf_split_block(buffer, block, size);
+        buffer->used_size += tlsf_block_get_size(block);
+
+        return ptr;
+    }
+
+    uint8_t* result = tlsf_memory_buffer_alloc(buffer, new_size);
+
+    if (result) {
+        memcpy(result, ptr, block_size);
+        tlsf_memory_buffer_free_block(buffer, ptr);
+    }
+
+    return result;
+}
+
+extern "C" uint64_t tlsf_memory_buffer_get_block_size(TlsfMemoryBuffer const* buffer, uint8_t const* ptr) {
+    assert(buffer != 0);
+    assert(ptr > buffer->base && ptr < buffer->base + buffer->size);
+
+    TlsfBlock const* block = tlsf_block_from_payload(ptr);
+    assert(!tlsf_block_is_free(block));
+
+    return tlsf_block_get_size(block);
+}
+
+extern "C" uint64_t tlsf_memory_buffer_get_max_alloc_size(TlsfMemoryBuffer const* buffer) {
+    assert(buffer != 0);
+
+    if (!buffer->base) {
+        return 0;
+    }
+
+    TlsfControl const* control = (TlsfControl const*) buffer->control;
+
+    if (!control->fl_bitmap) {
+        return 0;
+    }
+
+    // Requests are rounded up to the next class, so only the sizes up to the start
+    // of the largest non-empty class are guaranteed to find a block.
+    uint32_t fl = tlsf_find_last_set(control->fl_bitmap);
+    uint32_t sl = tlsf_find_last_set(control->sl_bitmaps[fl]);
+
+    return tlsf_mapping_class_size(fl, sl);
+}
+
+extern "C" void tlsf_memory_buffer_free_block(TlsfMemoryBuffer* buffer, uint8_t* ptr) {
+    assert(buffer != 0);
+    assert(ptr > buffer->base && ptr < buffer->base + buffer->size);
+
+    TlsfBlock* block = tlsf_block_from_payload(ptr);
+    assert(!tlsf_block_is_free(block));
+
+    buffer->used_size -= tlsf_block_get_size(block);
+
+    // Merge the block with the free neighbours.
+    TlsfBlock* previous = block->previous_physical;
+
+    if (previous && tlsf_block_is_free(previous)) {
+        tlsf_remove_block(buffer, previous);
+        previous->size += TLSF_BLOCK_HEADER_SIZE + tlsf_block_get_size(block);
+        block = previous;
+    }
+
+    TlsfBlock* next = tlsf_block_get_next(block);
+
+    if (tlsf_block_is_free(next)) {
+        tlsf_remove_block(buffer, next);
+        block->size += TLSF_BLOCK_HEADER_SIZE + tlsf_block_get_size(next);
+        next = tlsf_block_get_next(block);
+    }
+
+    next->previous_physical = block;
+    tlsf_insert_block(buffer, block);
+}
+
+extern "C" void tlsf_memory_buffer_free(TlsfMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    if (!buffer->base) {
+        return;
+    }
+
+    memset(buffer->control, 0, sizeof(TlsfControl));
+    buffer->used_size = 0;
+
+    // The whole pool is a single free block followed by an empty used sentinel block.
+    TlsfBlock* block = (TlsfBlock*) buffer->base;
+    block->previous_physical = 0;
+    block->size = buffer->size - 2 * TLSF_BLOCK_HEADER_SIZE;
+
+    TlsfBlock* sentinel = tlsf_block_get_next(block);
+    sentinel->previous_physical = block;
+    sentinel->size = 0;
+
+    tlsf_insert_block(buffer, block);
+}
@@ -1,6 +1,31 @@
 #include "vm_memory.hpp"
 #include <assert.h>
 
+static uint64_t align_to_page(uint64_t size) {
+    uint64_t page_size = virtual_page_size();
+    return (size + page_size - 1) & ~(page_size - 1);
+}
+
+// Make sure the memory of the buffer is committed up to the `end` offset.
+static bool region_memory_buffer_commit(RegionMemoryBuffer* buffer, uint64_t end) {
+    if (end <= buffer->committed) {
+        return true;
+    }
+
+    uint64_t commit_end = align_to_page(end);
+
+    if (commit_end > buffer->size) {
+        commit_end = buffer->size;
+    }
+
+    if (!virtual_commit(buffer->base + buffer->committed, commit_end - buffer->committed)) {
+        return false;
+    }
+
+    buffer->committed = commit_end;
+    return true;
+}
+
 extern "C" RegionMemoryBuffer create_region_memory_buffer(uint64_t size) {
     uint8_t* base = virtual_alloc(size);
     RegionMemoryBuffer buffer;
@@ -9,48 +34,228 @@ extern "C" RegionMemoryBuffer create_region_memory_buffer(uint64_t size) {
         buffer.size = size;
         buffer.base = base;
         buffer.offset = 0;
+        buffer.committed = size;
+        buffer.initial_commit = size;
+        buffer.owns_memory = true;
+        buffer.decommit_on_free = false;
+        buffer.poison_pattern = 0;
+        buffer.clear_policy = REGION_CLEAR_KEEP_DATA;
     }
     else {
         buffer.size = 0;
         buffer.base = 0;
         buffer.offset = 0;
+        buffer.committed = 0;
+        buffer.initial_commit = 0;
+        buffer.owns_memory = false;
+        buffer.decommit_on_free = false;
+        buffer.poison_pattern = 0;
+        buffer.clear_policy = REGION_CLEAR_KEEP_DATA;
     }
 
     return buffer;
 }
 
-extern "C" RegionMemoryBuffer region_memory_buffer_emplace_region(RegionMemoryBuffer* where, uint64_t size) {
-    assert(where->offset + size <= where->size);
+extern "C" RegionMemoryBuffer reserve_region_memory_buffer(uint64_t size, uint64_t initial_commit) {
+    uint8_t* base = virtual_reserve(size);
     RegionMemoryBuffer buffer;
 
-    buffer.base = where->base + where->offset;
-    buffer.size = size;
+    buffer.size = 0;
+    buffer.base = 0;
     buffer.offset = 0;
+    buffer.committed = 0;
+    buffer.initial_commit = 0;
+    buffer.owns_memory = false;
+    buffer.decommit_on_free = false;
+    buffer.poison_pattern = 0;
+    buffer.clear_policy = REGION_CLEAR_KEEP_DATA;
+
+    if (!base) {
+        return buffer;
+    }
 
-    where->offset += size;
+    buffer.size = size;
+    buffer.base = base;
+    buffer.owns_memory = true;
+
+    if (!region_memory_buffer_commit(&buffer, initial_commit)) {
+        region_memory_buffer_destroy(&buffer);
+        return buffer;
+    }
+
+    buffer.initial_commit = buffer.committed;
+    return buffer;
+}
+
+extern "C" void region_memory_buffer_destroy(RegionMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    if (buffer->owns_memory && buffer->base) {
+        virtual_free(buffer->base, buffer->size);
+    }
+
+    buffer->size = 0;
+    buffer->base = 0;
+    buffer->offset = 0;
+    buffer->committed = 0;
+    buffer->initial_commit = 0;
+    buffer->owns_memory = false;
+    buffer->decommit_on_free = false;
+    buffer->poison_pattern = 0;
+    buffer->clear_policy = REGION_CLEAR_KEEP_DATA;
+}
+
+extern "C" RegionMemoryBuffer region_memory_buffer_emplace_region(RegionMemoryBuffer* where, uint64_t size) {
+    assert(where != 0);
+    uint8_t* base = region_memory_buffer_alloc(where, size);
+    RegionMemoryBuffer buffer;
+
+    if (base) {
+        buffer.size = size;
+        buffer.base = base;
+        buffer.committed = size;
+        buffer.initial_commit = size;
+    }
+    else {
+        buffer.size = 0;
+        buffer.base = 0;
+        buffer.committed = 0;
+        buffer.initial_commit = 0;
+    }
+
+    buffer.offset = 0;
+    buffer.owns_memory = false;
+    buffer.decommit_on_free = false;
+    buffer.poison_pattern = 0;
+    buffer.clear_policy = REGION_CLEAR_KEEP_DATA;
 
     return buffer;
 }
 
 extern "C" uint8_t* region_memory_buffer_alloc(RegionMemoryBuffer* buffer, uint64_t size) {
+    return region_memory_buffer_alloc_aligned(buffer, size, 1);
+}
+
+extern "C" uint8_t* region_memory_buffer_alloc_aligned(RegionMemoryBuffer* buffer, uint64_t size, uint64_t align) {
     assert(buffer != 0);
 
-    if (buffer->offset + size > buffer->size) {
+    if (align == 0 || (align & (align - 1)) != 0) {
+        return 0;
+    }
+
+    uintptr_t address = (uintptr_t) (buffer->base + buffer->offset);
+    uint64_t padding = (align - (address & (align - 1))) & (align - 1);
+    uint64_t available = buffer->size - buffer->offset;
+
+    if (padding > available || size > available - padding) {
+        return 0;
+    }
+
+    if (!region_memory_buffer_commit(buffer, buffer->offset + padding + size)) {
         return 0;
     }
 
-    uint8_t* result = buffer->base + buffer->offset;
-    buffer->offset += size;
+    uint8_t* result = buffer->base + buffer->offset + padding;
+    buffer->offset += padding + size;
+
+    return result;
+}
+
+extern "C" uint8_t* region_memory_buffer_realloc(RegionMemoryBuffer* buffer, uint8_t* ptr, uint64_t old_size, uint64_t new_size, uint64_t align) {
+    assert(buffer != 0);
+
+    if (!ptr) {
+        return region_memory_buffer_alloc_aligned(buffer, new_size, align);
+    }
+
+    assert(ptr >= buffer->base && ptr + old_size <= buffer->base + buffer->offset);
+    uint64_t ptr_offset = ptr - buffer->base;
+
+    // The top allocation grows or shrinks in place.
+    if (ptr_offset + old_size == buffer->offset) {
+        if (new_size > buffer->size - ptr_offset) {
+            return 0;
+        }
+
+        if (!region_memory_buffer_commit(buffer, ptr_offset + new_size)) {
+            return 0;
+        }
+
+        buffer->offset = ptr_offset + new_size;
+        return ptr;
+    }
+
+    if (new_size <= old_size) {
+        return ptr;
+    }
+
+    uint8_t* result = region_memory_buffer_alloc_aligned(buffer, new_size, align);
+
+    if (result) {
+        memcpy(result, ptr, old_size);
+    }
 
     return result;
 }
 
 extern "C" uint8_t* region_memory_buffer_emplace(RegionMemoryBuffer* buffer, uint64_t size, uint8_t const* data) {
-    uint8_t* result = region_memory_buffer_alloc(buffer, size);
-    memcpy(result, data, size);
+    return region_memory_buffer_emplace_aligned(buffer, size, 1, data);
+}
+
+extern "C" uint8_t* region_memory_buffer_emplace_aligned(RegionMemoryBuffer* buffer, uint64_t size, uint64_t align, uint8_t const* data) {
+    uint8_t* result = region_memory_buffer_alloc_aligned(buffer, size, align);
+
+    if (result && size > 0) {
+        memcpy(result, data, size);
+    }
+
     return result;
 }
 
+extern "C" uintptr_t region_memory_buffer_get_marker(RegionMemoryBuffer const* buffer) {
+    assert(buffer != 0);
+    return buffer->offset;
+}
+
+extern "C" void region_memory_buffer_reset_to_marker(RegionMemoryBuffer* buffer, uintptr_t marker) {
+    assert(buffer != 0);
+    assert(marker <= buffer->offset);
+    buffer->offset = marker;
+}
+
 extern "C" void region_memory_buffer_free(RegionMemoryBuffer* buffer) {
+    assert(buffer != 0);
+
+    switch (buffer->clear_policy) {
+        case REGION_CLEAR_KEEP_DATA:
+            break;
+
+        case REGION_CLEAR_ZERO:
+            memset(buffer->base, 0, buffer->offset);
+            break;
+
+        case REGION_CLEAR_POISON:
+            memset(buffer->base, buffer->poison_pattern, buffer->offset);
+            break;
+
+        case REGION_CLEAR_RELEASE: {
+            // Only the pages that are completely used by the region can be released.
+            uint64_t page_size = virtual_page_size();
+            uintptr_t start = ((uintptr_t) buffer->base + page_size - 1) & ~(page_size - 1);
+            uintptr_t end = ((uintptr_t) buffer->base + buffer->offset) & ~(page_size - 1);
+
+            if (start < end) {
+                virtual_release((uint8_t*) start, end - start);
+            }
+
+            break;
+        }
+    }
+
     buffer->offset = 0;
+
+    if (buffer->decommit_on_free && buffer->committed > buffer->initial_commit) {
+        virtual_decommit(buffer->base + buffer->initial_commit, buffer->committed - buffer->initial_commit);
+        buffer->committed = buffer->initial_commit;
+    }
 }